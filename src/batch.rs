@@ -0,0 +1,43 @@
+use crate::{canonicalize_hand_in_place, Card};
+use std::collections::HashMap;
+
+/// Canonicalize many hands, keeping their order
+///
+/// Each output is allocated once at the size of its input and canonicalized
+/// in place, so the batch costs one allocation per hand plus the outer `Vec`.
+/// Panics if any hand has fewer than two cards, as `canonicalize_hand` does.
+pub fn canonicalize_batch(hands: &[Vec<Card>]) -> Vec<Vec<Card>> {
+    hands.iter().map(|hand| canonicalize_copy(hand)).collect()
+}
+
+/// Canonicalize many hands across threads, keeping their order
+///
+/// Produces the same result as `canonicalize_batch`.
+#[cfg(feature = "rayon")]
+pub fn par_canonicalize_batch(hands: &[Vec<Card>]) -> Vec<Vec<Card>> {
+    use rayon::prelude::*;
+    hands
+        .par_iter()
+        .map(|hand| canonicalize_copy(hand))
+        .collect()
+}
+
+fn canonicalize_copy(hand: &[Card]) -> Vec<Card> {
+    let mut canonical = hand.to_vec();
+    canonicalize_hand_in_place(&mut canonical);
+    canonical
+}
+
+/// Count how many of `hands` fall into each canonical class
+///
+/// Keys are canonical hands as `canonicalize_hand` gives them, so the counts
+/// sum to the number of input hands. Each hand is canonicalized in its own
+/// allocation, which becomes the key the first time its class is seen.
+pub fn canonical_histogram(hands: impl IntoIterator<Item = Vec<Card>>) -> HashMap<Vec<Card>, u64> {
+    let mut histogram = HashMap::new();
+    for mut hand in hands {
+        canonicalize_hand_in_place(&mut hand);
+        *histogram.entry(hand).or_insert(0) += 1;
+    }
+    histogram
+}
@@ -0,0 +1,89 @@
+//! Blackjack card values and hand totals.
+
+use crate::{Card, Value};
+
+/// Points a card of `value` counts for, with the ace counting 1.
+pub fn card_points(value: Value) -> u32 {
+    match value {
+        Value::Ace => 1,
+        Value::Jack | Value::Queen | Value::King => 10,
+        value => value as u32,
+    }
+}
+
+/// Best total of a blackjack hand.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HandTotal {
+    /// The highest total not above 21, or the lowest total if the hand is bust.
+    pub total: u32,
+    /// Whether an ace is counting 11, so drawing can't bust the hand.
+    pub soft: bool,
+}
+
+impl HandTotal {
+    /// Whether the total is above 21.
+    pub fn is_bust(&self) -> bool {
+        self.total > 21
+    }
+}
+
+/// Total a blackjack hand, counting one ace as 11 when that doesn't bust it.
+pub fn hand_total(cards: &[Card]) -> HandTotal {
+    let hard: u32 = cards.iter().map(|card| card_points(card.value())).sum();
+    let soft = hard <= 11 && cards.iter().any(|card| card.value() == Value::Ace);
+    HandTotal {
+        total: if soft { hard + 10 } else { hard },
+        soft,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_hand_total() {
+        let total = |values: &[Value]| {
+            let cards: Vec<_> = values.iter().map(|value| value.of(Spades)).collect();
+            hand_total(&cards)
+        };
+        assert_eq!(
+            total(&[Ace, King]),
+            HandTotal {
+                total: 21,
+                soft: true
+            }
+        );
+        assert_eq!(
+            total(&[Ace, Ace, Nine]),
+            HandTotal {
+                total: 21,
+                soft: true
+            }
+        );
+        assert_eq!(
+            total(&[Ace, Six, Nine]),
+            HandTotal {
+                total: 16,
+                soft: false
+            }
+        );
+        assert_eq!(
+            total(&[Ten, Queen]),
+            HandTotal {
+                total: 20,
+                soft: false
+            }
+        );
+        assert!(total(&[Ten, Queen, Two]).is_bust());
+        assert_eq!(
+            total(&[]),
+            HandTotal {
+                total: 0,
+                soft: false
+            }
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{canonicalize_hand_in_place, Card, CANONICAL_DECK};
+
+/// Longest hand the cache stores; longer hands are canonicalized directly.
+pub const MAX_CACHED_CARDS: usize = 8;
+
+/// Fixed-size memo of canonical hands, for workloads which revisit hands
+///
+/// Each hand of up to `MAX_CACHED_CARDS` cards is packed into a single `u64`
+/// from the deck indices of its cards, in order, and its canonical form is
+/// stored packed the same way. The cache is direct-mapped: each key has one
+/// slot, and a new hand simply replaces whatever was there, so lookups and
+/// insertions cost a hash and one comparison.
+///
+/// ```
+/// use canonical_hand::{canonicalize_hand, CanonicalCache, Suit::*, Value::*};
+///
+/// let mut cache = CanonicalCache::new(1 << 16);
+/// let hand = vec![Ace.of(Spades), King.of(Spades), Two.of(Hearts)];
+/// assert_eq!(cache.canonicalize(hand.clone()), canonicalize_hand(hand.clone()));
+/// assert_eq!(cache.canonicalize(hand.clone()), canonicalize_hand(hand));
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CanonicalCache {
+    // (packed hand, packed canonical hand), with a zero key marking an empty slot
+    slots: Vec<(u64, u64)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl CanonicalCache {
+    /// Create a cache with room for at least `capacity` hands.
+    ///
+    /// The capacity is rounded up to a power of two.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: vec![(0, 0); capacity.max(1).next_power_of_two()],
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Canonicalize a hand as `canonicalize_hand` does, using the cache.
+    pub fn canonicalize(&mut self, mut cards: Vec<Card>) -> Vec<Card> {
+        self.canonicalize_in_place(&mut cards);
+        cards
+    }
+
+    /// Canonicalize a hand in place as `canonicalize_hand_in_place` does, using the cache.
+    pub fn canonicalize_in_place(&mut self, cards: &mut [Card]) {
+        let key = match pack(cards) {
+            Some(key) => key,
+            None => return canonicalize_hand_in_place(cards),
+        };
+
+        let slot = self.slot(key);
+        if self.slots[slot].0 == key {
+            self.hits += 1;
+            unpack(self.slots[slot].1, cards);
+        } else {
+            self.misses += 1;
+            canonicalize_hand_in_place(cards);
+            self.slots[slot] = (key, pack(cards).unwrap());
+        }
+    }
+
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of lookups which had to canonicalize, not counting hands too long to cache.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Forget every stored hand and reset the counters.
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = (0, 0));
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        let mixed = key.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        (mixed >> 32) as usize & (self.slots.len() - 1)
+    }
+}
+
+// six bits per deck index, with the length in the top byte so no key is zero
+fn pack(cards: &[Card]) -> Option<u64> {
+    if cards.len() < 2 || cards.len() > MAX_CACHED_CARDS {
+        return None;
+    }
+    let packed = cards.iter().enumerate().fold(0, |packed, (i, card)| {
+        packed | (card.deck_index() as u64) << (6 * i)
+    });
+    Some(packed | (cards.len() as u64) << 56)
+}
+
+fn unpack(packed: u64, cards: &mut [Card]) {
+    for (i, card) in cards.iter_mut().enumerate() {
+        *card = CANONICAL_DECK[(packed >> (6 * i) & 63) as usize];
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{canonicalize_hand, Card};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Canonical hand for use as a map key, with its hash computed once
+///
+/// Construction canonicalizes the hand, so keys for isomorphic hands are
+/// equal. Hashing a key writes only the cached hash, and equality checks the
+/// cached hash before comparing cards, so map probes avoid walking the cards.
+///
+/// There is deliberately no `Borrow<[Card]>`: a borrowed slice would have to
+/// hash exactly as the key does, which the cached hash does not, and a raw
+/// slice need not be canonical. Look up with `CanonicalKey::new` instead.
+#[derive(Clone, Debug)]
+pub struct CanonicalKey {
+    cards: Vec<Card>,
+    hash: u64,
+}
+
+impl CanonicalKey {
+    /// Canonicalize a hand into a key.
+    ///
+    /// Panics if there are fewer than two cards, as `canonicalize_hand` does.
+    pub fn new(cards: Vec<Card>) -> Self {
+        let cards = canonicalize_hand(cards);
+        let mut hasher = DefaultHasher::new();
+        cards.hash(&mut hasher);
+        Self {
+            hash: hasher.finish(),
+            cards,
+        }
+    }
+
+    /// The canonical cards, hole cards first.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Take the canonical cards, hole cards first.
+    pub fn into_cards(self) -> Vec<Card> {
+        self.cards
+    }
+}
+
+impl PartialEq for CanonicalKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.cards == other.cards
+    }
+}
+
+impl Eq for CanonicalKey {}
+
+impl Hash for CanonicalKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl AsRef<[Card]> for CanonicalKey {
+    fn as_ref(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+impl From<Vec<Card>> for CanonicalKey {
+    fn from(cards: Vec<Card>) -> Self {
+        Self::new(cards)
+    }
+}
+
+impl From<CanonicalKey> for Vec<Card> {
+    fn from(key: CanonicalKey) -> Self {
+        key.cards
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{canonicalize_hand, Card, Hand};
+
+/// Hand representations which can be canonicalized by value.
+pub trait Canonicalize {
+    /// The strategically equivalent hand with lexicographic minimum.
+    fn canonicalized(self) -> Self;
+}
+
+impl Canonicalize for Vec<Card> {
+    fn canonicalized(self) -> Self {
+        canonicalize_hand(self)
+    }
+}
+
+impl Canonicalize for Hand {
+    fn canonicalized(self) -> Self {
+        self.canonicalize()
+    }
+}
+
+/// Adds `.canonicalized()` to iterators of hands.
+///
+/// ```
+/// use canonical_hand::{CanonicalizeExt, Suit::*, Value::*};
+///
+/// let hands = vec![
+///     vec![Ace.of(Spades), King.of(Spades)],
+///     vec![Ace.of(Hearts), King.of(Hearts)],
+/// ];
+/// let canonical: Vec<_> = hands.into_iter().canonicalized().collect();
+/// assert_eq!(canonical[0], canonical[1]);
+/// ```
+pub trait CanonicalizeExt: Iterator + Sized
+where
+    Self::Item: Canonicalize,
+{
+    /// Canonicalize each hand as it is produced.
+    fn canonicalized(self) -> Canonicalized<Self> {
+        Canonicalized { iter: self }
+    }
+}
+
+impl<I> CanonicalizeExt for I
+where
+    I: Iterator,
+    I::Item: Canonicalize,
+{
+}
+
+/// Iterator returned by `CanonicalizeExt::canonicalized`.
+#[derive(Clone, Debug)]
+pub struct Canonicalized<I> {
+    iter: I,
+}
+
+impl<I> Iterator for Canonicalized<I>
+where
+    I: Iterator,
+    I::Item: Canonicalize,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.iter.next().map(Canonicalize::canonicalized)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
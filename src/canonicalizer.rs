@@ -0,0 +1,102 @@
+use crate::zones::canonicalize_zone_in_place;
+use crate::{Card, SuitPermutation};
+
+/// Incremental Hold'em canonicalization, fed one street at a time.
+///
+/// Produces the same canonical form as `canonicalize_holdem`: the hole cards
+/// and flop are each unordered, and the turn and river keep their identity.
+/// This is guaranteed after every street: `canonical()` equals
+/// `canonicalize_holdem` of the cards dealt so far, or `canonicalize_hand` of
+/// the hole cards alone, so incremental and batch canonicalization always
+/// agree. `testing::assert_incremental_matches_batch` checks this for a deal.
+///
+/// Each street only examines its own cards against the relabellings that were
+/// still minimal after the previous street, so earlier streets are never
+/// recomputed and their canonical cards never change as later streets arrive.
+///
+/// Each `push_*` method returns the delta relabelling from the canonical suits
+/// before the street to the canonical suits after it, i.e. `permutation()`
+/// after the push composed with the inverse of `permutation()` before it.
+/// Per-suit data keyed by the previous street's canonical suits can be carried
+/// forward by relabelling it with the delta. The delta only ever swaps suits
+/// which the earlier streets left interchangeable, so it maps the earlier
+/// canonical cards onto themselves.
+///
+/// ```
+/// use canonical_hand::{Canonicalizer, Suit::*, Value::*};
+///
+/// let mut canonicalizer = Canonicalizer::new();
+/// canonicalizer.push_hole([Ace.of(Spades), King.of(Spades)]);
+/// canonicalizer.push_flop([Two.of(Hearts), Seven.of(Spades), Nine.of(Hearts)]);
+/// assert_eq!(canonicalizer.canonical()[..2], [King.of(Clubs), Ace.of(Clubs)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Canonicalizer {
+    canonical: Vec<Card>,
+    candidates: Vec<SuitPermutation>,
+}
+
+impl Canonicalizer {
+    pub fn new() -> Self {
+        Self {
+            canonical: Vec::with_capacity(7),
+            candidates: SuitPermutation::all().collect(),
+        }
+    }
+
+    /// Deal the hole cards. Panics unless this is the first street.
+    pub fn push_hole(&mut self, hole: [Card; 2]) -> SuitPermutation {
+        assert!(self.canonical.is_empty(), "hole cards already dealt");
+        self.push_street(&hole)
+    }
+
+    /// Deal the flop. Panics unless the hole cards have been dealt.
+    pub fn push_flop(&mut self, flop: [Card; 3]) -> SuitPermutation {
+        assert_eq!(self.canonical.len(), 2, "flop must follow the hole cards");
+        self.push_street(&flop)
+    }
+
+    /// Deal the turn. Panics unless the flop has been dealt.
+    pub fn push_turn(&mut self, turn: Card) -> SuitPermutation {
+        assert_eq!(self.canonical.len(), 5, "turn must follow the flop");
+        self.push_street(&[turn])
+    }
+
+    /// Deal the river. Panics unless the turn has been dealt.
+    pub fn push_river(&mut self, river: Card) -> SuitPermutation {
+        assert_eq!(self.canonical.len(), 6, "river must follow the turn");
+        self.push_street(&[river])
+    }
+
+    /// Canonical cards dealt so far: hole, flop, turn, river.
+    pub fn canonical(&self) -> &[Card] {
+        &self.canonical
+    }
+
+    /// A relabelling from the original suits to the canonical suits.
+    ///
+    /// This is the first of `permutations()`, and may change as streets are
+    /// dealt when earlier streets left suits interchangeable.
+    pub fn permutation(&self) -> SuitPermutation {
+        self.candidates[0]
+    }
+
+    /// Every relabelling which maps the cards dealt so far to the canonical cards.
+    pub fn permutations(&self) -> &[SuitPermutation] {
+        &self.candidates
+    }
+
+    fn push_street(&mut self, cards: &[Card]) -> SuitPermutation {
+        let previous = self.permutation();
+        let start = self.canonical.len();
+        self.canonical.extend_from_slice(cards);
+        canonicalize_zone_in_place(&mut self.canonical[start..], false, &mut self.candidates);
+        self.permutation().compose(&previous.inverse())
+    }
+}
+
+impl Default for Canonicalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
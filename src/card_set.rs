@@ -0,0 +1,392 @@
+use crate::{Card, Suit, CANONICAL_DECK};
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Not, Sub};
+use strum::IntoEnumIterator;
+
+/// Set of cards stored as a 64-bit mask.
+///
+/// Each suit occupies a 16-bit lane (Clubs in the lowest lane, Spades in the
+/// highest), and within a lane bit `n` is set for the card with value `n + 2`.
+/// Only the low 13 bits of each lane are ever used.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Add a card to the set, returning false if it was already present.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let bit = Self::bit(card);
+        let added = self.0 & bit == 0;
+        self.0 |= bit;
+        added
+    }
+
+    /// Remove a card from the set, returning false if it was not present.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let bit = Self::bit(card);
+        let removed = self.0 & bit != 0;
+        self.0 &= !bit;
+        removed
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & Self::bit(card) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// 13-bit mask of the values present in `suit`, bit `n` being value `n + 2`.
+    pub fn suit_mask(&self, suit: Suit) -> u16 {
+        (self.0 >> (suit as u64 * 16)) as u16
+    }
+
+    /// 13-bit mask of the values present in any suit, bit `n` being value `n + 2`.
+    pub fn value_mask(&self) -> u16 {
+        let lanes = self.0;
+        (lanes | lanes >> 16 | lanes >> 32 | lanes >> 48) as u16
+    }
+
+    /// Build a set from positions in `CANONICAL_DECK`.
+    ///
+    /// Panics if any index is 52 or above.
+    pub fn from_deck_indices(indices: &[u8]) -> Self {
+        Self(
+            indices
+                .iter()
+                .fold(0, |bits, index| bits | DECK_INDEX_BITS[*index as usize]),
+        )
+    }
+
+    /// Append the `CANONICAL_DECK` positions of the cards in the set to `out`, in deck order.
+    pub fn extend_deck_indices(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        let mut bits = self.0;
+        while bits != 0 {
+            out.push(LANE_BIT_DECK_INDEX[bits.trailing_zeros() as usize]);
+            bits &= bits - 1;
+        }
+        out[start..].sort_unstable();
+    }
+
+    pub fn to_deck_indices(&self) -> Vec<u8> {
+        let mut indices = Vec::with_capacity(self.len());
+        self.extend_deck_indices(&mut indices);
+        indices
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The cards of `self` which are not in `other`.
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Every card of the deck which is not in the set.
+    pub const fn complement(self) -> Self {
+        Self(!self.0 & FULL_DECK_BITS)
+    }
+
+    pub const fn is_subset(self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub const fn is_disjoint(self, other: Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Iterate the cards in `CANONICAL_DECK` order, i.e. by value then suit.
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+
+    /// The cards in `CANONICAL_DECK` order.
+    pub fn to_vec(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+
+    /// The raw mask, for storage; see the type documentation for the layout.
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Rebuild a set from `to_bits`, or None if a bit outside the deck is set.
+    pub const fn try_from_bits(bits: u64) -> Option<Self> {
+        if bits & !FULL_DECK_BITS == 0 {
+            Some(Self(bits))
+        } else {
+            None
+        }
+    }
+
+    /// The suit relabelling of the set whose cards, in deck order, are
+    /// lexicographically least.
+    ///
+    /// This is `canonicalize_cards` for a set, where no card is distinguished.
+    /// It works on whole suit lanes: the lane holding the lowest cards, compared
+    /// from the lowest value upwards, becomes clubs, and so on.
+    pub fn canonicalize(self) -> Self {
+        let mut lanes = [0u16; 4];
+        for (lane, suit) in lanes.iter_mut().zip(Suit::iter()) {
+            *lane = self.suit_mask(suit);
+        }
+        lanes.sort_unstable_by_key(|lane| std::cmp::Reverse(lane.reverse_bits()));
+        Self(
+            lanes
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (i, lane)| bits | (*lane as u64) << (i * 16)),
+        )
+    }
+
+    pub(crate) const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    #[inline]
+    const fn bit(card: Card) -> u64 {
+        1 << (card.suit() as u64 * 16 + card.value() as u64 - 2)
+    }
+}
+
+/// Convert a flat array of deck indices, `hand_size` per hand, into one set per hand.
+///
+/// Panics if the length of `indices` is not a multiple of `hand_size`.
+pub fn card_sets_from_deck_indices(indices: &[u8], hand_size: usize) -> Vec<CardSet> {
+    assert!(
+        hand_size > 0 && indices.len().is_multiple_of(hand_size),
+        "indices must hold a whole number of hands"
+    );
+    indices
+        .chunks_exact(hand_size)
+        .map(CardSet::from_deck_indices)
+        .collect()
+}
+
+/// Convert sets into a flat array of deck indices, each set's indices in deck order.
+pub fn deck_indices_from_card_sets(sets: &[CardSet]) -> Vec<u8> {
+    let total = sets.iter().map(CardSet::len).sum();
+    let mut indices = Vec::with_capacity(total);
+    for set in sets {
+        set.extend_deck_indices(&mut indices);
+    }
+    indices
+}
+
+// Bit in the set for each position in CANONICAL_DECK
+const DECK_INDEX_BITS: [u64; 52] = {
+    let mut bits = [0; 52];
+    let mut index = 0;
+    while index < 52 {
+        bits[index] = 1 << ((index % 4) * 16 + index / 4);
+        index += 1;
+    }
+    bits
+};
+
+// Position in CANONICAL_DECK for each bit in the set; unused bits map to 0xFF
+const LANE_BIT_DECK_INDEX: [u8; 64] = {
+    let mut indices = [0xFF; 64];
+    let mut bit = 0;
+    while bit < 64 {
+        if bit % 16 < 13 {
+            indices[bit] = ((bit % 16) * 4 + bit / 16) as u8;
+        }
+        bit += 1;
+    }
+    indices
+};
+
+// Every card of the deck: the low 13 bits of each lane
+const FULL_DECK_BITS: u64 = 0x1FFF_1FFF_1FFF_1FFF;
+
+/// Iterator over a `CardSet` in `CANONICAL_DECK` order.
+#[derive(Clone, Debug)]
+pub struct CardSetIter(u64);
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        let set = CardSet(self.0);
+        if set.is_empty() {
+            return None;
+        }
+        // lowest value present, then the lowest suit holding it
+        let value = set.value_mask().trailing_zeros() as usize;
+        let suit = (0..4).find(|suit| self.0 >> (suit * 16 + value) & 1 != 0)?;
+        self.0 &= !(1 << (suit * 16 + value));
+        Some(CANONICAL_DECK[value * 4 + suit])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().copied().collect()
+    }
+}
+
+impl From<CardSet> for Vec<Card> {
+    fn from(set: CardSet) -> Self {
+        set.to_vec()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+impl Not for CardSet {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for card in iter {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+    use crate::CANONICAL_DECK;
+
+    #[test]
+    fn test_card_set_contains_inserted() {
+        let set: CardSet = CANONICAL_DECK.iter().copied().step_by(3).collect();
+        for (i, card) in CANONICAL_DECK.iter().enumerate() {
+            assert_eq!(set.contains(*card), i % 3 == 0);
+        }
+        assert_eq!(set.len(), 18);
+    }
+
+    #[test]
+    fn test_card_set_deck_indices() {
+        for (index, card) in CANONICAL_DECK.iter().enumerate() {
+            let set = CardSet::from_deck_indices(&[index as u8]);
+            assert!(set.contains(*card));
+            assert_eq!(set.to_deck_indices(), vec![index as u8]);
+        }
+
+        let indices = [51, 0, 17, 3, 40, 12, 2, 33, 50, 1];
+        let sets = card_sets_from_deck_indices(&indices, 5);
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].len(), 5);
+        assert_eq!(
+            deck_indices_from_card_sets(&sets),
+            vec![0, 3, 17, 40, 51, 1, 2, 12, 33, 50]
+        );
+    }
+
+    #[test]
+    fn test_card_set_masks() {
+        let set: CardSet = vec![
+            Two.of(Clubs),
+            Ace.of(Clubs),
+            Five.of(Hearts),
+            Two.of(Spades),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.suit_mask(Clubs), 0b1_0000_0000_0001);
+        assert_eq!(set.suit_mask(Diamonds), 0);
+        assert_eq!(set.suit_mask(Hearts), 0b1000);
+        assert_eq!(set.suit_mask(Spades), 0b1);
+        assert_eq!(set.value_mask(), 0b1_0000_0000_1001);
+    }
+
+    #[test]
+    fn test_card_set_operations() {
+        let a: CardSet = CANONICAL_DECK[..10].iter().copied().collect();
+        let b: CardSet = CANONICAL_DECK[5..15].iter().copied().collect();
+        assert_eq!((a | b).len(), 15);
+        assert_eq!((a & b).to_vec(), CANONICAL_DECK[5..10].to_vec());
+        assert_eq!((a - b).to_vec(), CANONICAL_DECK[..5].to_vec());
+        assert_eq!((!a).to_vec(), CANONICAL_DECK[10..].to_vec());
+        assert!((a & b).is_subset(a) && !a.is_subset(b));
+        assert!((a - b).is_disjoint(b));
+
+        let mut set = a;
+        assert!(set.remove(Two.of(Clubs)));
+        assert!(!set.remove(Two.of(Clubs)));
+        assert_eq!(set.len(), 9);
+    }
+
+    #[test]
+    fn test_card_set_iteration_and_bits() {
+        let cards = vec![
+            Ace.of(Spades),
+            Two.of(Hearts),
+            Two.of(Clubs),
+            Nine.of(Diamonds),
+        ];
+        let set = CardSet::from(cards.as_slice());
+        let mut sorted = cards;
+        sorted.sort();
+        assert_eq!(set.iter().len(), 4);
+        assert_eq!(Vec::from(set), sorted);
+        assert_eq!(CardSet::try_from_bits(set.to_bits()), Some(set));
+        assert_eq!(CardSet::try_from_bits(1 << 13), None);
+        assert_eq!((!CardSet::new()).to_vec(), CANONICAL_DECK.to_vec());
+    }
+}
@@ -1,7 +1,12 @@
 use num_derive::FromPrimitive;
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 
-use std::fmt::Debug;
+use std::convert::TryInto;
+use std::fmt::{Debug, Display};
+use std::num::NonZeroU8;
+use std::str::FromStr;
+
+use crate::ParseCardError;
 
 use Suit::*;
 use Value::*;
@@ -25,7 +30,89 @@ pub enum Value {
 
 impl Value {
     pub const fn of(self, suit: Suit) -> Card {
-        Card { value: self, suit }
+        match NonZeroU8::new(self.packed_rank() * 4 + suit.packed_suit() + 1) {
+            Some(code) => Card(code),
+            None => unreachable!(),
+        }
+    }
+
+    /// Parse a value from its standard single character: `2`-`9`, `T`, `J`, `Q`, `K`, `A`.
+    ///
+    /// Letters are accepted in either case.
+    pub const fn from_char(c: char) -> Option<Value> {
+        Some(match c {
+            '2' => Two,
+            '3' => Three,
+            '4' => Four,
+            '5' => Five,
+            '6' => Six,
+            '7' => Seven,
+            '8' => Eight,
+            '9' => Nine,
+            'T' | 't' => Ten,
+            'J' | 'j' => Jack,
+            'Q' | 'q' => Queen,
+            'K' | 'k' => King,
+            'A' | 'a' => Ace,
+            _ => return None,
+        })
+    }
+
+    /// The standard single character for the value, as in `Card`'s `Display`.
+    pub const fn to_char(self) -> char {
+        match self {
+            Two => '2',
+            Three => '3',
+            Four => '4',
+            Five => '5',
+            Six => '6',
+            Seven => '7',
+            Eight => '8',
+            Nine => '9',
+            Ten => 'T',
+            Jack => 'J',
+            Queen => 'Q',
+            King => 'K',
+            Ace => 'A',
+        }
+    }
+
+    // rank used in the packed card encoding; see Card::to_packed
+    const fn packed_rank(self) -> u8 {
+        match self {
+            Two => 0,
+            Three => 1,
+            Four => 2,
+            Five => 3,
+            Six => 4,
+            Seven => 5,
+            Eight => 6,
+            Nine => 7,
+            Ten => 8,
+            Jack => 9,
+            Queen => 10,
+            King => 11,
+            Ace => 12,
+        }
+    }
+
+    const fn from_packed_rank(rank: u8) -> Option<Value> {
+        Some(match rank {
+            0 => Two,
+            1 => Three,
+            2 => Four,
+            3 => Five,
+            4 => Six,
+            5 => Seven,
+            6 => Eight,
+            7 => Nine,
+            8 => Ten,
+            9 => Jack,
+            10 => Queen,
+            11 => King,
+            12 => Ace,
+            _ => return None,
+        })
     }
 
     const fn shorthand(self) -> &'static str {
@@ -56,6 +143,60 @@ pub enum Suit {
 }
 
 impl Suit {
+    /// Parse a suit from its standard single character: `c`, `d`, `h`, `s`.
+    ///
+    /// Letters are accepted in either case.
+    pub const fn from_char(c: char) -> Option<Suit> {
+        Some(match c {
+            'c' | 'C' => Clubs,
+            'd' | 'D' => Diamonds,
+            'h' | 'H' => Hearts,
+            's' | 'S' => Spades,
+            _ => return None,
+        })
+    }
+
+    /// The standard single character for the suit, as in `Card`'s `Display`.
+    pub const fn to_char(self) -> char {
+        match self {
+            Clubs => 'c',
+            Diamonds => 'd',
+            Hearts => 'h',
+            Spades => 's',
+        }
+    }
+
+    // suit used in the packed card encoding; see Card::to_packed
+    const fn packed_suit(self) -> u8 {
+        match self {
+            Clubs => 0,
+            Diamonds => 1,
+            Hearts => 2,
+            Spades => 3,
+        }
+    }
+
+    const fn from_packed_suit(suit: u8) -> Option<Suit> {
+        Some(match suit {
+            0 => Clubs,
+            1 => Diamonds,
+            2 => Hearts,
+            3 => Spades,
+            _ => return None,
+        })
+    }
+
+    // the card-game symbols, filled or outlined
+    const fn from_symbol(c: char) -> Option<Suit> {
+        Some(match c {
+            '♣' | '♧' => Clubs,
+            '♦' | '♢' => Diamonds,
+            '♥' | '♡' => Hearts,
+            '♠' | '♤' => Spades,
+            _ => return None,
+        })
+    }
+
     const fn shorthand(self) -> &'static str {
         match self {
             Clubs => "C",
@@ -66,23 +207,225 @@ impl Suit {
     }
 }
 
+/// A playing card, stored in a single byte.
+///
+/// The byte is one more than the card's position in `CANONICAL_DECK`, so it is
+/// never zero and `Option<Card>` is also a single byte. Ordering follows the
+/// deck: by value, then by suit.
 #[derive(PartialOrd, PartialEq, Copy, Clone, Eq, Ord, Hash)]
-pub struct Card {
-    pub value: Value,
-    pub suit: Suit,
+pub struct Card(NonZeroU8);
+
+impl Card {
+    pub const fn value(self) -> Value {
+        match Value::from_packed_rank(self.0.get().wrapping_sub(1) / 4) {
+            Some(value) => value,
+            None => unreachable!(),
+        }
+    }
+
+    pub const fn suit(self) -> Suit {
+        match Suit::from_packed_suit(self.0.get().wrapping_sub(1) % 4) {
+            Some(suit) => suit,
+            None => unreachable!(),
+        }
+    }
+
+    /// The card of the same value in `suit`.
+    pub const fn with_suit(self, suit: Suit) -> Card {
+        self.value().of(suit)
+    }
+
+    /// Stable one-byte encoding of the card, for serialization.
+    ///
+    /// The byte is `4 * rank + suit`, with rank counting from Two = 0 up to
+    /// Ace = 12 and suit being Clubs = 0, Diamonds = 1, Hearts = 2, Spades = 3.
+    /// The encoding is written out explicitly rather than derived from the
+    /// enum discriminants, so persisted data stays readable even if the enums
+    /// change.
+    pub const fn to_packed(self) -> u8 {
+        self.0.get() - 1
+    }
+
+    /// Decode a byte produced by `to_packed`, or None if it is not a card.
+    pub const fn from_packed(byte: u8) -> Option<Card> {
+        match (
+            Value::from_packed_rank(byte / 4),
+            Suit::from_packed_suit(byte % 4),
+        ) {
+            (Some(value), Some(suit)) => Some(value.of(suit)),
+            _ => None,
+        }
+    }
+
+    /// Parse exactly two characters: an uppercase value then a lowercase suit, as in `As`.
+    ///
+    /// This is the form to use for machine-generated input, where anything
+    /// else indicates a bug upstream. It is also the `FromStr` implementation.
+    pub fn parse_strict(s: &str) -> Result<Card, ParseCardError> {
+        let mut chars = s.chars();
+        let (value, suit) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(value), Some(suit), None) => (value, suit),
+            _ => return Err(ParseCardError::InvalidLength),
+        };
+        let value = Value::from_char(value)
+            .filter(|_| !value.is_ascii_lowercase())
+            .ok_or(ParseCardError::InvalidValue(value))?;
+        let suit = Suit::from_char(suit)
+            .filter(|_| suit.is_ascii_lowercase())
+            .ok_or(ParseCardError::InvalidSuit(suit))?;
+        Ok(value.of(suit))
+    }
+
+    /// Parse a card as a human might type it.
+    ///
+    /// Surrounding whitespace and whitespace between value and suit are
+    /// ignored, letters may be in either case, `10` is accepted for Ten, and
+    /// suits may be given as symbols such as `♠`. So `" 10 ♥"`, `"th"` and
+    /// `"TH"` are all the ten of hearts.
+    pub fn parse_lenient(s: &str) -> Result<Card, ParseCardError> {
+        let s = s.trim();
+        let (value, rest) = if let Some(rest) = s.strip_prefix("10") {
+            (Ten, rest)
+        } else {
+            let mut chars = s.chars();
+            let value = chars.next().ok_or(ParseCardError::InvalidLength)?;
+            let value = Value::from_char(value).ok_or(ParseCardError::InvalidValue(value))?;
+            (value, chars.as_str())
+        };
+
+        let mut chars = rest.trim_start().chars();
+        let suit = match (chars.next(), chars.next()) {
+            (Some(suit), None) => suit,
+            _ => return Err(ParseCardError::InvalidLength),
+        };
+        let suit = Suit::from_char(suit)
+            .or_else(|| Suit::from_symbol(suit))
+            .ok_or(ParseCardError::InvalidSuit(suit))?;
+        Ok(value.of(suit))
+    }
+
+    /// The card in Cactus Kev's 32-bit encoding, used by many hand evaluators.
+    ///
+    /// From the most significant bit down, the word holds one bit per rank,
+    /// one bit per suit (clubs highest), the rank from Two = 0 and the rank's
+    /// prime from 2 up to 41:
+    /// `xxxAKQJT 98765432 CDHSrrrr xxpppppp`.
+    pub const fn to_cactus_kev(self) -> u32 {
+        let rank = self.value().packed_rank() as u32;
+        let suit = 0x8000 >> self.suit().packed_suit();
+        1 << (16 + rank) | suit | rank << 8 | CACTUS_KEV_PRIMES[rank as usize]
+    }
+
+    /// Decode a card from `to_cactus_kev`, or None unless the word is exactly
+    /// one card's encoding.
+    pub const fn from_cactus_kev(word: u32) -> Option<Card> {
+        let rank = (word >> 8 & 0xF) as u8;
+        // clubs is the highest of the four suit bits
+        let suit = ((word >> 12 & 0xF).leading_zeros() - 28) as u8;
+        let card = match (Value::from_packed_rank(rank), Suit::from_packed_suit(suit)) {
+            (Some(value), Some(suit)) => value.of(suit),
+            _ => return None,
+        };
+        if card.to_cactus_kev() == word {
+            Some(card)
+        } else {
+            None
+        }
+    }
+
+    /// Position of this card in `CANONICAL_DECK`.
+    pub const fn deck_index(self) -> usize {
+        self.0.get() as usize - 1
+    }
+
+    /// Position of this card in `CANONICAL_DECK`, as a byte.
+    pub const fn index(self) -> u8 {
+        self.0.get() - 1
+    }
+
+    /// The card at `index` in `CANONICAL_DECK`, or None if `index` is 52 or above.
+    pub const fn from_index(index: u8) -> Option<Card> {
+        if index < 52 {
+            Some(CANONICAL_DECK[index as usize])
+        } else {
+            None
+        }
+    }
 }
 
+// prime for each rank in the Cactus Kev encoding, from Two up
+const CACTUS_KEV_PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
 impl Debug for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
             "Card({}{})",
-            self.value.shorthand(),
-            self.suit.shorthand()
+            self.value().shorthand(),
+            self.suit().shorthand()
         )
     }
 }
 
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        Card::parse_strict(s)
+    }
+}
+
+/// Formats as the standard two characters, as in `As` or `Td`.
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.value(), self.suit())
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Parses exactly one uppercase character, as `Card::parse_strict` expects.
+impl FromStr for Value {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Value, ParseCardError> {
+        let value = single_char(s)?;
+        Value::from_char(value)
+            .filter(|_| !value.is_ascii_lowercase())
+            .ok_or(ParseCardError::InvalidValue(value))
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Parses exactly one lowercase character, as `Card::parse_strict` expects.
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Suit, ParseCardError> {
+        let suit = single_char(s)?;
+        Suit::from_char(suit)
+            .filter(|_| suit.is_ascii_lowercase())
+            .ok_or(ParseCardError::InvalidSuit(suit))
+    }
+}
+
+fn single_char(s: &str) -> Result<char, ParseCardError> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(ParseCardError::InvalidLength),
+    }
+}
+
 pub const CANONICAL_DECK: [Card; 52] = [
     Two.of(Clubs),
     Two.of(Diamonds),
@@ -137,3 +480,48 @@ pub const CANONICAL_DECK: [Card; 52] = [
     Ace.of(Hearts),
     Ace.of(Spades),
 ];
+
+/// The 36-card short deck (Six and up) used by Six Plus Hold'em, in the same order as `CANONICAL_DECK`.
+///
+/// Canonicalization doesn't depend on which cards are in the deck, so short
+/// deck hands canonicalize with the same functions; only hand strength
+/// differs, see `short_deck_straight_from_rank_mask`.
+pub const CANONICAL_SHORT_DECK: [Card; 36] = {
+    let mut deck = [Six.of(Clubs); 36];
+    let mut index = 0;
+    while index < 36 {
+        deck[index] = CANONICAL_DECK[index + 16];
+        index += 1;
+    }
+    deck
+};
+
+/// Position of each card in `CANONICAL_DECK`, indexed by `[value as usize - 2][suit as usize]`.
+pub const DECK_INDEX: [[u8; 4]; 13] = {
+    let mut table = [[0; 4]; 13];
+    let mut value = 0;
+    while value < 13 {
+        let mut suit = 0;
+        while suit < 4 {
+            table[value][suit] = (value * 4 + suit) as u8;
+            suit += 1;
+        }
+        value += 1;
+    }
+    table
+};
+
+/// The four cards of `value` in `CANONICAL_DECK`, in suit order.
+pub fn cards_of_value(value: Value) -> &'static [Card; 4] {
+    let start = value.of(Clubs).deck_index();
+    CANONICAL_DECK[start..start + 4].try_into().unwrap()
+}
+
+/// The thirteen cards of `suit`, in value order.
+pub fn cards_of_suit(suit: Suit) -> [Card; 13] {
+    let mut cards = [Two.of(suit); 13];
+    for (card, value) in cards.iter_mut().zip(Value::iter()) {
+        *card = value.of(suit);
+    }
+    cards
+}
@@ -0,0 +1,135 @@
+use crate::{Card, CardSet, CANONICAL_DECK};
+
+/// Iterator over all n-bit masks with k bits set, bit `i` standing for item `i`.
+///
+/// Masks are produced in lexicographic order of the item indices they select.
+/// Internally this walks the complements of bit-reversed masks upwards with
+/// Gosper's hack: reversing makes lower items more significant, so
+/// lexicographic order is decreasing order, which is increasing order of the
+/// complement.
+#[derive(Clone, Debug)]
+struct BitCombinations {
+    next_complement: Option<u64>,
+    n: usize,
+}
+
+impl BitCombinations {
+    fn new(n: usize, k: usize) -> Self {
+        assert!(n < 64, "too many items to combine");
+        Self {
+            next_complement: (k <= n).then(|| (1 << (n - k)) - 1),
+            n,
+        }
+    }
+}
+
+impl Iterator for BitCombinations {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let complement = self.next_complement?;
+        let full = (1 << self.n) - 1;
+        self.next_complement = if complement == 0 {
+            None
+        } else {
+            // Gosper's hack: the next larger integer with the same number of bits set
+            let lowest = complement & complement.wrapping_neg();
+            let ripple = complement + lowest;
+            let next = (((ripple ^ complement) >> 2) / lowest) | ripple;
+            (next <= full).then_some(next)
+        };
+
+        let reversed = !complement & full;
+        Some(
+            reversed
+                .reverse_bits()
+                .checked_shr(64 - self.n as u32)
+                .unwrap_or(0),
+        )
+    }
+}
+
+/// Iterate every way of choosing `k` of `cards`.
+///
+/// Each combination keeps the cards in their order within `cards`, and
+/// combinations are produced in lexicographic order of their positions in
+/// `cards`, so sorted input gives sorted output. Panics if there are 64 or
+/// more cards.
+pub fn combinations(cards: &[Card], k: usize) -> impl Iterator<Item = Vec<Card>> + '_ {
+    BitCombinations::new(cards.len(), k).map(move |mut mask| {
+        let mut combination = Vec::with_capacity(k);
+        while mask != 0 {
+            combination.push(cards[mask.trailing_zeros() as usize]);
+            mask &= mask - 1;
+        }
+        combination
+    })
+}
+
+/// Iterate every set of `k` cards from the deck which avoids the `dead` cards.
+///
+/// Sets are produced in lexicographic order of their cards in deck order,
+/// which suits merge-joins against other sorted streams of hands.
+pub fn combinations_with_dead(dead: CardSet, k: usize) -> impl Iterator<Item = CardSet> {
+    let mut live = CANONICAL_DECK;
+    let mut live_count = 0;
+    for card in CANONICAL_DECK.iter().filter(|card| !dead.contains(**card)) {
+        live[live_count] = *card;
+        live_count += 1;
+    }
+
+    BitCombinations::new(live_count, k).map(move |mut mask| {
+        let mut set = CardSet::new();
+        while mask != 0 {
+            set.insert(live[mask.trailing_zeros() as usize]);
+            mask &= mask - 1;
+        }
+        set
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choose(n: usize, k: usize) -> usize {
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn test_combinations_counts() {
+        let cards = &CANONICAL_DECK[..10];
+        for k in 0..=11 {
+            let all: Vec<_> = combinations(cards, k).collect();
+            assert_eq!(all.len(), if k <= 10 { choose(10, k) } else { 0 });
+            assert!(all.iter().all(|combination| combination.len() == k));
+            assert!(all.iter().all(|combination| combination.is_sorted()));
+            assert!(all.is_sorted());
+        }
+    }
+
+    #[test]
+    fn test_combinations_with_dead() {
+        let dead: CardSet = CANONICAL_DECK[..2].iter().copied().collect();
+        let mut count = 0;
+        for set in combinations_with_dead(dead, 2) {
+            assert_eq!(set.len(), 2);
+            assert!(CANONICAL_DECK[..2].iter().all(|card| !set.contains(*card)));
+            count += 1;
+        }
+        assert_eq!(count, choose(50, 2));
+
+        let hands: Vec<_> = combinations_with_dead(dead, 3)
+            .map(|set| {
+                let mut cards: Vec<_> = CANONICAL_DECK
+                    .iter()
+                    .filter(|card| set.contains(**card))
+                    .collect();
+                cards.sort();
+                cards
+            })
+            .collect();
+        assert!(hands.is_sorted());
+        assert_eq!(combinations_with_dead(CardSet::new(), 0).count(), 1);
+    }
+}
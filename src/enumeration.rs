@@ -0,0 +1,128 @@
+use crate::{combinations, combinations_with_dead, Card, CardSet, SuitPermutation, CANONICAL_DECK};
+
+/// Iterate every canonical hand with `hole_size` hole cards and `board_size` board cards, with its weight
+///
+/// Each hand is produced once, exactly as `canonicalize_hand_with_hole_count`
+/// would produce it, together with the number of concrete hands in its class.
+/// The weights therefore sum to the number of ways to deal the hand, e.g.
+/// `1326 * 19600` for Hold'em on the flop. Hands come in lexicographic order of
+/// their hole cards, then of their board.
+pub fn enumerate_canonical(
+    hole_size: usize,
+    board_size: usize,
+) -> impl Iterator<Item = (Vec<Card>, u32)> {
+    combinations(&CANONICAL_DECK, hole_size)
+        .filter_map(|hole| {
+            let stabilizer = canonical_stabilizer(&hole, SuitPermutation::all())?;
+            Some((hole, stabilizer))
+        })
+        .flat_map(move |(hole, stabilizer)| {
+            let dead: CardSet = hole.iter().copied().collect();
+            combinations_with_dead(dead, board_size)
+                .filter_map(move |board| weighted_hand(&hole, board.iter().collect(), &stabilizer))
+        })
+}
+
+/// Join a canonical hole to a sorted board with its weight, if the board is
+/// canonical given the hole's stabilizer.
+fn weighted_hand(
+    hole: &[Card],
+    board: Vec<Card>,
+    stabilizer: &[SuitPermutation],
+) -> Option<(Vec<Card>, u32)> {
+    let automorphisms = canonical_stabilizer(&board, stabilizer.iter().copied())?;
+    let mut hand = hole.to_vec();
+    hand.extend(board);
+    Some((hand, 24 / automorphisms.len() as u32))
+}
+
+/// Enumerate canonical hands across threads, as `enumerate_canonical` does.
+///
+/// The work is split by hole cards and lowest board card, and rayon keeps the
+/// pieces in order, so collecting gives exactly the output of
+/// `enumerate_canonical` however many threads run.
+#[cfg(feature = "rayon")]
+pub fn par_enumerate_canonical(
+    hole_size: usize,
+    board_size: usize,
+) -> impl rayon::iter::ParallelIterator<Item = (Vec<Card>, u32)> {
+    use rayon::prelude::*;
+
+    let holes: Vec<_> = combinations(&CANONICAL_DECK, hole_size)
+        .filter_map(|hole| {
+            let stabilizer = canonical_stabilizer(&hole, SuitPermutation::all())?;
+            Some((hole, stabilizer))
+        })
+        .collect();
+
+    // each piece is a hole with the deck position of the lowest board card
+    let mut pieces = Vec::new();
+    for (hole_index, (hole, _)) in holes.iter().enumerate() {
+        if board_size == 0 {
+            pieces.push((hole_index, None));
+        } else {
+            pieces.extend(
+                (0..CANONICAL_DECK.len())
+                    .filter(|first| !hole.contains(&CANONICAL_DECK[*first]))
+                    .map(|first| (hole_index, Some(first))),
+            );
+        }
+    }
+
+    pieces
+        .into_par_iter()
+        .flat_map_iter(move |(hole_index, first)| {
+            let (hole, stabilizer) = holes[hole_index].clone();
+            let lower = first.map_or(0, |first| first + 1);
+            let dead: CardSet = hole
+                .iter()
+                .chain(&CANONICAL_DECK[..lower])
+                .copied()
+                .collect();
+            let first = first.map(|first| CANONICAL_DECK[first]);
+            let rest_size = board_size - first.iter().count();
+            combinations_with_dead(dead, rest_size).filter_map(move |rest| {
+                let board = first.iter().copied().chain(rest.iter()).collect();
+                weighted_hand(&hole, board, &stabilizer)
+            })
+        })
+}
+
+/// The permutations among `candidates` which map the sorted `cards` to themselves,
+/// or None if one of them maps it to something smaller.
+fn canonical_stabilizer(
+    cards: &[Card],
+    candidates: impl Iterator<Item = SuitPermutation>,
+) -> Option<Vec<SuitPermutation>> {
+    let mut stabilizer = Vec::new();
+    let mut image = Vec::with_capacity(cards.len());
+    for permutation in candidates {
+        image.clear();
+        image.extend(cards.iter().map(|card| permutation.apply(*card)));
+        image.sort_unstable();
+        match image.as_slice().cmp(cards) {
+            std::cmp::Ordering::Less => return None,
+            std::cmp::Ordering::Equal => stabilizer.push(permutation),
+            std::cmp::Ordering::Greater => {}
+        }
+    }
+    Some(stabilizer)
+}
+
+/// Iterate the canonical three-card boards lazily, in lexicographic order.
+///
+/// Boards are canonical on their own, with no hole cards, as `canonicalize_cards`
+/// defines; each of the 22,100 flops is the relabelling of exactly one of them.
+pub fn canonical_flops() -> impl Iterator<Item = Vec<Card>> {
+    enumerate_canonical(0, 3).map(|(board, _)| board)
+}
+
+/// Iterate the canonical four-card boards lazily, in lexicographic order.
+pub fn canonical_turns() -> impl Iterator<Item = Vec<Card>> {
+    enumerate_canonical(0, 4).map(|(board, _)| board)
+}
+
+/// Iterate the canonical five-card boards lazily, in lexicographic order.
+pub fn canonical_rivers() -> impl Iterator<Item = Vec<Card>> {
+    enumerate_canonical(0, 5).map(|(board, _)| board)
+}
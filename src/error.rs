@@ -0,0 +1,52 @@
+use crate::Card;
+use std::fmt;
+
+/// Reasons a hand cannot be canonicalized.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CanonicalizeError {
+    /// The hand has fewer than the two hole cards.
+    TooFewCards(usize),
+    /// The same card appears more than once in the hand.
+    DuplicateCard(Card),
+}
+
+impl fmt::Display for CanonicalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CanonicalizeError::TooFewCards(count) => {
+                write!(f, "hand needs at least two cards, got {}", count)
+            }
+            CanonicalizeError::DuplicateCard(card) => {
+                write!(f, "card {:?} appears more than once", card)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CanonicalizeError {}
+
+/// Reasons a string cannot be parsed as a card.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseCardError {
+    /// The string has the wrong number of characters: two for a card, one
+    /// for a value or suit.
+    InvalidLength,
+    /// The value character is not recognised.
+    InvalidValue(char),
+    /// The suit character is not recognised.
+    InvalidSuit(char),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::InvalidLength => write!(f, "wrong number of characters for a card"),
+            ParseCardError::InvalidValue(c) => write!(f, "invalid card value {:?}", c),
+            ParseCardError::InvalidSuit(c) => write!(f, "invalid card suit {:?}", c),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
@@ -0,0 +1,137 @@
+use crate::{CardSet, Suit, Value};
+use num_traits::FromPrimitive;
+use strum::IntoEnumIterator;
+
+/// Find the highest straight in a 13-bit rank mask (bit `n` being value `n + 2`).
+///
+/// Returns the top value of the straight. The ace also plays low, so the
+/// wheel (A-2-3-4-5) is reported as a Five-high straight.
+pub fn straight_from_rank_mask(mask: u16) -> Option<Value> {
+    // shift up one so that bit 0 can hold the low ace
+    let extended = (mask as u32) << 1 | (mask as u32 >> 12 & 1);
+    let runs = extended & extended >> 1 & extended >> 2 & extended >> 3 & extended >> 4;
+    if runs == 0 {
+        return None;
+    }
+
+    // top card of the run is four above its lowest bit, and bit n is value n + 1
+    let lowest_bit_of_highest_run = 31 - runs.leading_zeros();
+    Value::from_u32(lowest_bit_of_highest_run + 5)
+}
+
+/// Find the highest deuce-to-seven straight in a 13-bit rank mask.
+///
+/// As `straight_from_rank_mask`, but the ace only plays high, so A-2-3-4-5
+/// is not a straight. Deuce-to-seven hands otherwise need nothing special:
+/// flushes count as usual, and hands canonicalize with the default
+/// `ValueOrder::AceHigh`, e.g. through `canonicalize_draw`.
+pub fn deuce_to_seven_straight_from_rank_mask(mask: u16) -> Option<Value> {
+    // the only Five-high straight is the wheel
+    straight_from_rank_mask(mask).filter(|top| *top != Value::Five)
+}
+
+/// Find the highest short deck straight in a 13-bit rank mask.
+///
+/// As `straight_from_rank_mask`, but values below Six are ignored and the
+/// ace plays low immediately below the Six, so A-6-7-8-9 is a Nine-high
+/// straight.
+pub fn short_deck_straight_from_rank_mask(mask: u16) -> Option<Value> {
+    const BELOW_SIX: u16 = 0b1111;
+    const FIVE: u16 = 1 << 3;
+    let ace_low = if mask >> 12 & 1 == 1 { FIVE } else { 0 };
+    straight_from_rank_mask(mask & !BELOW_SIX | ace_low)
+}
+
+/// Find a suit with at least five cards in the set.
+///
+/// If several suits qualify (only possible with ten or more cards) the lowest
+/// suit is returned.
+pub fn flush_suit(cards: &CardSet) -> Option<Suit> {
+    Suit::iter().find(|suit| cards.suit_mask(*suit).count_ones() >= 5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    fn mask(values: &[Value]) -> u16 {
+        values
+            .iter()
+            .fold(0, |mask, value| mask | 1 << (*value as u16 - 2))
+    }
+
+    #[test]
+    fn test_straight_from_rank_mask() {
+        assert_eq!(straight_from_rank_mask(0), None);
+        assert_eq!(
+            straight_from_rank_mask(mask(&[Ace, Two, Three, Four, Five])),
+            Some(Five)
+        );
+        assert_eq!(
+            straight_from_rank_mask(mask(&[Ten, Jack, Queen, King, Ace])),
+            Some(Ace)
+        );
+        assert_eq!(
+            straight_from_rank_mask(mask(&[Ace, Two, Three, Four, Five, Six, Seven])),
+            Some(Seven)
+        );
+        assert_eq!(
+            straight_from_rank_mask(mask(&[Queen, King, Ace, Two, Three])),
+            None
+        );
+        assert_eq!(
+            straight_from_rank_mask(mask(&[Two, Three, Four, Five, Seven])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_deuce_to_seven_straight_from_rank_mask() {
+        assert_eq!(
+            deuce_to_seven_straight_from_rank_mask(mask(&[Ace, Two, Three, Four, Five])),
+            None
+        );
+        assert_eq!(
+            deuce_to_seven_straight_from_rank_mask(mask(&[Ace, Two, Three, Four, Five, Six])),
+            Some(Six)
+        );
+        assert_eq!(
+            deuce_to_seven_straight_from_rank_mask(mask(&[Ten, Jack, Queen, King, Ace])),
+            Some(Ace)
+        );
+    }
+
+    #[test]
+    fn test_short_deck_straight_from_rank_mask() {
+        assert_eq!(
+            short_deck_straight_from_rank_mask(mask(&[Ace, Six, Seven, Eight, Nine])),
+            Some(Nine)
+        );
+        assert_eq!(
+            short_deck_straight_from_rank_mask(mask(&[Ten, Jack, Queen, King, Ace])),
+            Some(Ace)
+        );
+        assert_eq!(
+            short_deck_straight_from_rank_mask(mask(&[Ace, Two, Three, Four, Five])),
+            None
+        );
+        assert_eq!(
+            short_deck_straight_from_rank_mask(mask(&[Five, Six, Seven, Eight, Nine])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_flush_suit() {
+        let mut cards: CardSet = [Two, Five, Nine, Jack]
+            .iter()
+            .map(|value| value.of(Hearts))
+            .collect();
+        cards.insert(Ace.of(Spades));
+        assert_eq!(flush_suit(&cards), None);
+        cards.insert(King.of(Hearts));
+        assert_eq!(flush_suit(&cards), Some(Hearts));
+    }
+}
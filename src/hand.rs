@@ -0,0 +1,179 @@
+use crate::{
+    canonicalize_hand_with_permutation, CanonicalizeError, Card, CardSet, SuitPermutation,
+};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+
+/// Hold'em hand with the hole cards kept apart from the board.
+///
+/// The flat `Vec<Card>` functions treat the first two cards as the hole;
+/// this type makes that split explicit. The hand also tracks which cards it
+/// holds, so dealing a street only checks the new cards.
+#[derive(Clone, Debug)]
+pub struct Hand {
+    hole: [Card; 2],
+    board: Vec<Card>,
+    used: CardSet,
+}
+
+impl Hand {
+    pub fn new(hole: [Card; 2], board: Vec<Card>) -> Self {
+        let used = hole.iter().chain(&board).copied().collect();
+        Self { hole, board, used }
+    }
+
+    /// Create a hand, failing with the first repeated card if any card appears twice.
+    pub fn try_new(hole: [Card; 2], board: Vec<Card>) -> Result<Self, CanonicalizeError> {
+        let mut hand = Self::new(hole, Vec::with_capacity(board.len()));
+        if hand.used.len() < 2 {
+            return Err(CanonicalizeError::DuplicateCard(hole[1]));
+        }
+        hand.try_extend_board(&board)?;
+        Ok(hand)
+    }
+
+    /// Add cards to the board, failing without changing the hand if any card is
+    /// already in the hand or repeated among `cards`.
+    ///
+    /// Only the new cards are examined, so this costs O(1) per card dealt.
+    pub fn try_extend_board(&mut self, cards: &[Card]) -> Result<(), CanonicalizeError> {
+        let mut used = self.used;
+        for card in cards {
+            if !used.insert(*card) {
+                return Err(CanonicalizeError::DuplicateCard(*card));
+            }
+        }
+        self.used = used;
+        self.board.extend_from_slice(cards);
+        Ok(())
+    }
+
+    pub fn hole(&self) -> [Card; 2] {
+        self.hole
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    /// Every card in the hand.
+    pub fn used(&self) -> CardSet {
+        self.used
+    }
+
+    /// The strategically equivalent hand with lexicographic minimum.
+    pub fn canonicalize(&self) -> Hand {
+        self.canonicalize_with_permutation().0
+    }
+
+    /// Canonicalize, also returning the suit relabelling which was applied.
+    pub fn canonicalize_with_permutation(&self) -> (Hand, SuitPermutation) {
+        let (cards, permutation) = canonicalize_hand_with_permutation(self.to_vec());
+        (Hand::try_from(cards).unwrap(), permutation)
+    }
+
+    /// All cards, hole cards first.
+    pub fn to_vec(&self) -> Vec<Card> {
+        let mut cards = Vec::with_capacity(2 + self.board.len());
+        cards.extend_from_slice(&self.hole);
+        cards.extend_from_slice(&self.board);
+        cards
+    }
+}
+
+// the used cards follow from the hole and board, so they are left out of
+// comparisons and hashing
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        (self.hole, &self.board) == (other.hole, &other.board)
+    }
+}
+
+impl Eq for Hand {}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.hole, &self.board).cmp(&(other.hole, &other.board))
+    }
+}
+
+impl Hash for Hand {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hole.hash(state);
+        self.board.hash(state);
+    }
+}
+
+/// Split a flat hand, taking the first two cards as the hole.
+impl TryFrom<Vec<Card>> for Hand {
+    type Error = CanonicalizeError;
+
+    fn try_from(mut cards: Vec<Card>) -> Result<Self, Self::Error> {
+        if cards.len() < 2 {
+            return Err(CanonicalizeError::TooFewCards(cards.len()));
+        }
+        let board = cards.split_off(2);
+        Ok(Hand::new([cards[0], cards[1]], board))
+    }
+}
+
+impl From<Hand> for Vec<Card> {
+    fn from(hand: Hand) -> Self {
+        hand.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonicalize_hand;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_hand_canonicalize_matches_flat() {
+        let hand = Hand::new(
+            [Ace.of(Hearts), Ace.of(Spades)],
+            vec![Two.of(Spades), Seven.of(Hearts), King.of(Diamonds)],
+        );
+        let canonical = hand.canonicalize();
+        assert_eq!(canonical.to_vec(), canonicalize_hand(hand.to_vec()));
+        assert_eq!(canonical.hole(), [Ace.of(Clubs), Ace.of(Diamonds)]);
+    }
+
+    #[test]
+    fn test_hand_rejects_duplicates() {
+        assert_eq!(
+            Hand::try_new([Ace.of(Hearts), Two.of(Clubs)], vec![Ace.of(Hearts)]),
+            Err(CanonicalizeError::DuplicateCard(Ace.of(Hearts)))
+        );
+
+        let mut hand = Hand::try_new([Ace.of(Hearts), Two.of(Clubs)], vec![]).unwrap();
+        assert_eq!(
+            hand.try_extend_board(&[Three.of(Clubs), Two.of(Clubs)]),
+            Err(CanonicalizeError::DuplicateCard(Two.of(Clubs)))
+        );
+        assert!(hand.board().is_empty());
+        assert_eq!(hand.used().len(), 2);
+        assert_eq!(hand.try_extend_board(&[Three.of(Clubs)]), Ok(()));
+        assert_eq!(hand.board(), [Three.of(Clubs)]);
+    }
+
+    #[test]
+    fn test_hand_try_from_vec() {
+        assert_eq!(
+            Hand::try_from(vec![Ace.of(Hearts)]),
+            Err(CanonicalizeError::TooFewCards(1))
+        );
+        let hand = Hand::try_from(vec![Ace.of(Hearts), Two.of(Clubs), Three.of(Clubs)]).unwrap();
+        assert_eq!(hand.hole(), [Ace.of(Hearts), Two.of(Clubs)]);
+        assert_eq!(hand.board(), [Three.of(Clubs)]);
+    }
+}
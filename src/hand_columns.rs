@@ -0,0 +1,130 @@
+use crate::{Card, Suit, Value};
+use std::iter::FromIterator;
+
+/// Structure-of-arrays storage for a batch of hands.
+///
+/// The values and suits of every hand are stored back to back in two
+/// contiguous columns, so a batch of N hands costs two bytes per card plus
+/// one offset per hand, rather than a separate allocation per hand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandColumns {
+    values: Vec<Value>,
+    suits: Vec<Suit>,
+    // offsets[i]..offsets[i + 1] is the range of hand i in the columns
+    offsets: Vec<usize>,
+}
+
+impl HandColumns {
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            suits: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+
+    pub fn push(&mut self, hand: &[Card]) {
+        self.values.extend(hand.iter().map(|card| card.value()));
+        self.suits.extend(hand.iter().map(|card| card.suit()));
+        self.offsets.push(self.values.len());
+    }
+
+    /// Number of hands stored.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Values of the hand at `index`.
+    pub fn values(&self, index: usize) -> &[Value] {
+        &self.values[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    /// Suits of the hand at `index`.
+    pub fn suits(&self, index: usize) -> &[Suit] {
+        &self.suits[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    /// Copy out the hand at `index`.
+    pub fn hand(&self, index: usize) -> Vec<Card> {
+        self.values(index)
+            .iter()
+            .zip(self.suits(index))
+            .map(|(value, suit)| value.of(*suit))
+            .collect()
+    }
+
+    /// The value column for all hands, in order.
+    pub fn value_column(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// The suit column for all hands, in order.
+    pub fn suit_column(&self) -> &[Suit] {
+        &self.suits
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec<Card>> + '_ {
+        (0..self.len()).map(move |index| self.hand(index))
+    }
+}
+
+impl Default for HandColumns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: AsRef<[Card]>> FromIterator<H> for HandColumns {
+    fn from_iter<I: IntoIterator<Item = H>>(iter: I) -> Self {
+        let mut columns = Self::new();
+        for hand in iter {
+            columns.push(hand.as_ref());
+        }
+        columns
+    }
+}
+
+impl From<Vec<Vec<Card>>> for HandColumns {
+    fn from(hands: Vec<Vec<Card>>) -> Self {
+        hands.into_iter().collect()
+    }
+}
+
+impl From<HandColumns> for Vec<Vec<Card>> {
+    fn from(columns: HandColumns) -> Self {
+        columns.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_hand_columns_round_trip() {
+        let hands = vec![
+            vec![Ace.of(Spades), King.of(Hearts)],
+            vec![],
+            vec![
+                Two.of(Clubs),
+                Two.of(Diamonds),
+                Three.of(Hearts),
+                Four.of(Spades),
+                Five.of(Clubs),
+            ],
+        ];
+        let columns = HandColumns::from(hands.clone());
+
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns.values(0), &[Ace, King]);
+        assert_eq!(columns.suits(2), &[Clubs, Diamonds, Hearts, Spades, Clubs]);
+        assert_eq!(columns.value_column().len(), 7);
+        assert_eq!(Vec::<Vec<Card>>::from(columns), hands);
+    }
+}
@@ -0,0 +1,627 @@
+use crate::{canonicalize_hand, Card, CardSet, Hand, Suit, Value};
+use num_traits::FromPrimitive;
+use std::convert::TryFrom;
+use strum::IntoEnumIterator;
+
+/// A betting round of Hold'em, identified by the number of board cards.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// Number of board cards dealt by this street.
+    pub const fn board_len(self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+
+    /// The street with `len` board cards, if any.
+    pub const fn from_board_len(len: usize) -> Option<Street> {
+        Some(match len {
+            0 => Street::Preflop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            5 => Street::River,
+            _ => return None,
+        })
+    }
+
+    /// Number of canonical hands on this street, the bound of `canonical_index`.
+    pub const fn canonical_count(self) -> u64 {
+        match self {
+            Street::Preflop => CANONICAL_PREFLOP_HANDS,
+            Street::Flop => CANONICAL_FLOP_HANDS,
+            Street::Turn => CANONICAL_TURN_HANDS,
+            Street::River => CANONICAL_RIVER_HANDS,
+        }
+    }
+}
+
+/// Number of canonical Hold'em starting hands.
+pub const CANONICAL_PREFLOP_HANDS: u64 = 169;
+/// Number of canonical Hold'em hands of hole cards and a flop.
+pub const CANONICAL_FLOP_HANDS: u64 = 1_286_792;
+/// Number of canonical Hold'em hands of hole cards and a four-card board.
+pub const CANONICAL_TURN_HANDS: u64 = 13_960_050;
+/// Number of canonical Hold'em hands of hole cards and a five-card board.
+pub const CANONICAL_RIVER_HANDS: u64 = 123_156_254;
+
+/// Number of canonical three-card boards, as produced by `canonical_flops`.
+pub const CANONICAL_FLOPS: usize = 1_755;
+/// Number of canonical four-card boards, as produced by `canonical_turns`.
+pub const CANONICAL_TURNS: usize = 16_432;
+/// Number of canonical five-card boards, as produced by `canonical_rivers`.
+pub const CANONICAL_RIVERS: usize = 134_459;
+
+/// Dense index of a Hold'em hand's canonical class within its street
+///
+/// The first two cards are the hole cards and the remainder is the board,
+/// which must have 0, 3, 4 or 5 cards. Strategically equivalent hands share an
+/// index, and the indices of a street are exactly `0..street.canonical_count()`,
+/// so they can address a flat table. The hand need not be canonical.
+///
+/// The index is computed from the hole and board ranks held in each suit,
+/// without canonicalizing the hand or allocating, in constant time. With the
+/// `tables` feature, preflop hands are a single lookup in a table built at
+/// compile time, and the per-suit ranks of later streets come from another.
+/// There is no flop table: indexed by hole cards and flop it would need
+/// 1326 × 22100 entries, over 100 MB, and even keyed by the 169 hole card
+/// classes it would add 15 MB to every binary.
+///
+/// Panics if the hand has the wrong number of cards or repeats a card, since
+/// no hand has such an index and any value returned would belong to another.
+pub fn canonical_index(hand: &[Card]) -> u64 {
+    let street = hand
+        .len()
+        .checked_sub(2)
+        .and_then(Street::from_board_len)
+        .expect("hand must have two hole cards and 0, 3, 4 or 5 board cards");
+    let used: CardSet = hand.iter().copied().collect();
+    assert_eq!(used.len(), hand.len(), "hand must not repeat a card");
+
+    #[cfg(feature = "tables")]
+    if street == Street::Preflop {
+        return PREFLOP_INDEX[hand[0].deck_index()][hand[1].deck_index()] as u64;
+    }
+
+    compute_canonical_index(hand, street)
+}
+
+fn compute_canonical_index(hand: &[Card], street: Street) -> u64 {
+    let hole: CardSet = hand[..2].iter().copied().collect();
+    let board: CardSet = hand[2..].iter().copied().collect();
+
+    // each suit's holding, ordered as the layout expects
+    let mut suits = [((0, 0), 0); 4];
+    for (entry, suit) in suits.iter_mut().zip(Suit::iter()) {
+        let (hole, board) = (hole.suit_mask(suit), board.suit_mask(suit));
+        let sizes = (hole.count_ones() as u8, board.count_ones() as u8);
+        *entry = (sizes, holding_index(hole, board));
+    }
+    suits.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let sizes = [suits[0].0, suits[1].0, suits[2].0, suits[3].0];
+    let layouts = layouts(street);
+    let layout = &layouts[layouts
+        .binary_search_by(|layout| layout.sizes.cmp(&sizes))
+        .expect("every hand has a layout")];
+
+    let mut index = 0;
+    for group in suits.chunk_by(|a, b| a.0 == b.0) {
+        let (hole_len, board_len) = group[0].0;
+        let holdings = holding_count(hole_len, board_len);
+        let rank: u64 = group
+            .iter()
+            .enumerate()
+            .map(|(position, (_, holding))| choose(holding + position as u64, position as u64 + 1))
+            .sum();
+        index = index * multichoose(holdings, group.len() as u64) + rank;
+    }
+    layout.offset + index
+}
+
+/// The canonical hand with the given `canonical_index` on `street`
+///
+/// Inverts `canonical_index`, so table entries can be decoded back into cards.
+/// Panics unless `index` is below `street.canonical_count()`.
+pub fn hand_from_index(street: Street, index: u64) -> Hand {
+    assert!(
+        index < street.canonical_count(),
+        "index out of range for street"
+    );
+    let layouts = layouts(street);
+    let layout = &layouts[layouts.partition_point(|layout| layout.offset + layout.count <= index)];
+
+    // undo the mixed radix, last group first
+    let mut holdings = Vec::with_capacity(4);
+    let mut rest = index - layout.offset;
+    for group in layout.sizes.chunk_by(|a, b| a == b).rev() {
+        let (hole_len, board_len) = group[0];
+        let count = holding_count(hole_len, board_len);
+        let radix = multichoose(count, group.len() as u64);
+        let mut rank = rest % radix;
+        rest /= radix;
+
+        for position in (0..group.len() as u64).rev() {
+            // binary search for the largest holding whose contribution fits in the rank
+            let (mut holding, mut above) = (0, count);
+            while above - holding > 1 {
+                let middle = (holding + above) / 2;
+                if choose(middle + position, position + 1) <= rank {
+                    holding = middle;
+                } else {
+                    above = middle;
+                }
+            }
+            rank -= choose(holding + position, position + 1);
+            holdings.push(holding_from_index(hole_len, board_len, holding));
+        }
+    }
+
+    let mut cards = Vec::with_capacity(2 + street.board_len());
+    let mut board = Vec::with_capacity(street.board_len());
+    for ((hole, board_mask), suit) in holdings.into_iter().zip(Suit::iter()) {
+        cards.extend(cards_of_mask(hole, suit));
+        board.extend(cards_of_mask(board_mask, suit));
+    }
+    cards.extend(board);
+    Hand::try_from(canonicalize_hand(cards)).unwrap()
+}
+
+/// Map every index of an old indexing scheme to the current `canonical_index`
+///
+/// If the index scheme must ever change, keep the old `canonical_index` as
+/// `old_index` and use the map to rewrite tables keyed by it: the entry at
+/// position `i` is the current index of the class which `old_index` numbered
+/// `i`. Every street's indices fit in a `u32`. Panics unless `old_index`
+/// numbers the street's classes as exactly `0..street.canonical_count()`.
+pub fn index_migration_map(street: Street, old_index: impl Fn(&[Card]) -> u64) -> Vec<u32> {
+    let count = street.canonical_count() as usize;
+    let mut map = vec![u32::MAX; count];
+    for (hand, _) in crate::enumerate_canonical(2, street.board_len()) {
+        let old = old_index(&hand) as usize;
+        assert!(
+            old < count && map[old] == u32::MAX,
+            "old index {} is out of range or repeated",
+            old
+        );
+        map[old] = canonical_index(&hand) as u32;
+    }
+    map
+}
+
+const RANKS: u64 = 13;
+
+/// Number of hole and board cards in one suit.
+type Sizes = (u8, u8);
+
+/// The canonical hands whose suits hold a given multiset of sizes.
+#[derive(Copy, Clone, Debug)]
+struct Layout {
+    /// Sizes of each suit's holding, largest first.
+    sizes: [Sizes; 4],
+    /// Index of the first hand with this layout.
+    offset: u64,
+    /// Number of hands with this layout.
+    count: u64,
+}
+
+/// Most layouts on any street; the river has this many.
+const MAX_LAYOUTS: usize = 36;
+
+/// The layouts of a street in order, built at compile time.
+struct Layouts {
+    layouts: [Layout; MAX_LAYOUTS],
+    len: usize,
+}
+
+static LAYOUTS: [Layouts; 4] = [
+    build_layouts(2, 0),
+    build_layouts(2, 3),
+    build_layouts(2, 4),
+    build_layouts(2, 5),
+];
+
+fn layouts(street: Street) -> &'static [Layout] {
+    let layouts = &LAYOUTS[street as usize];
+    &layouts.layouts[..layouts.len]
+}
+
+// Sizes are coded as `hole * SIZE_CODES_PER_HOLE + board`, so codes order like sizes.
+const SIZE_CODES_PER_HOLE: u8 = 6;
+const SIZE_CODES: u8 = 3 * SIZE_CODES_PER_HOLE;
+
+const fn build_layouts(hole_len: u8, board_len: u8) -> Layouts {
+    let empty = Layout {
+        sizes: [(0, 0); 4],
+        offset: 0,
+        count: 0,
+    };
+    let mut layouts = Layouts {
+        layouts: [empty; MAX_LAYOUTS],
+        len: 0,
+    };
+    let mut offset = 0;
+
+    // every multiset of four sizes, largest first, in increasing order
+    let mut codes = [0u8; 4];
+    loop {
+        let mut sizes = [(0, 0); 4];
+        let (mut hole_total, mut board_total) = (0, 0);
+        let mut suit = 0;
+        while suit < 4 {
+            sizes[suit] = (
+                codes[suit] / SIZE_CODES_PER_HOLE,
+                codes[suit] % SIZE_CODES_PER_HOLE,
+            );
+            hole_total += sizes[suit].0;
+            board_total += sizes[suit].1;
+            suit += 1;
+        }
+
+        if hole_total == hole_len && board_total == board_len {
+            // suits with equal sizes are interchangeable, so hold a multiset of holdings
+            let mut count = 1;
+            let mut start = 0;
+            while start < 4 {
+                let mut end = start + 1;
+                while end < 4 && sizes[end].0 == sizes[start].0 && sizes[end].1 == sizes[start].1 {
+                    end += 1;
+                }
+                count *= multichoose(
+                    holding_count(sizes[start].0, sizes[start].1),
+                    (end - start) as u64,
+                );
+                start = end;
+            }
+
+            layouts.layouts[layouts.len] = Layout {
+                sizes,
+                offset,
+                count,
+            };
+            layouts.len += 1;
+            offset += count;
+        }
+
+        // step to the next non-increasing sequence of codes
+        let mut suit = 3;
+        while suit > 0 && codes[suit] == codes[suit - 1] {
+            suit -= 1;
+        }
+        if suit == 0 && codes[0] == SIZE_CODES - 1 {
+            return layouts;
+        }
+        codes[suit] += 1;
+        while suit < 3 {
+            suit += 1;
+            codes[suit] = 0;
+        }
+    }
+}
+
+/// Number of ways one suit can hold `hole_len` hole cards and `board_len` board cards.
+const fn holding_count(hole_len: u8, board_len: u8) -> u64 {
+    choose(RANKS, hole_len as u64) * choose(RANKS - hole_len as u64, board_len as u64)
+}
+
+/// Index of one suit's holding among those with the same sizes.
+fn holding_index(hole: u16, board: u16) -> u64 {
+    let free = RANKS - hole.count_ones() as u64;
+    let board_in_free_ranks = compress(board, hole);
+    subset_index(hole) * choose(free, board.count_ones() as u64) + subset_index(board_in_free_ranks)
+}
+
+/// Hole and board rank masks of the holding with the given `holding_index`.
+fn holding_from_index(hole_len: u8, board_len: u8, index: u64) -> (u16, u16) {
+    let board_count = choose(RANKS - hole_len as u64, board_len as u64);
+    let hole = subset_from_index(hole_len as u64, index / board_count);
+    let board = expand(
+        subset_from_index(board_len as u64, index % board_count),
+        hole,
+    );
+    (hole, board)
+}
+
+/// Colexicographic index of a set of ranks among sets of the same size.
+fn subset_index(mask: u16) -> u64 {
+    #[cfg(feature = "tables")]
+    return SUBSET_INDEX[mask as usize] as u64;
+
+    #[cfg(not(feature = "tables"))]
+    compute_subset_index(mask)
+}
+
+const fn compute_subset_index(mask: u16) -> u64 {
+    let mut index = 0;
+    let mut seen = 0;
+    let mut rank = 0;
+    while rank < RANKS {
+        if mask >> rank & 1 == 1 {
+            seen += 1;
+            index += choose(rank, seen);
+        }
+        rank += 1;
+    }
+    index
+}
+
+/// `subset_index` of every set of ranks, computed at compile time.
+#[cfg(feature = "tables")]
+static SUBSET_INDEX: [u16; 1 << RANKS] = {
+    let mut table = [0; 1 << RANKS];
+    let mut mask = 0;
+    while mask < table.len() {
+        table[mask] = compute_subset_index(mask as u16) as u16;
+        mask += 1;
+    }
+    table
+};
+
+/// `canonical_index` of every pair of hole cards, by deck index, computed at
+/// compile time. A repeated card's entry is meaningless.
+#[cfg(feature = "tables")]
+static PREFLOP_INDEX: [[u8; 52]; 52] = {
+    let mut table = [[0; 52]; 52];
+    let mut first = 0;
+    while first < 52 {
+        let mut second = 0;
+        while second < 52 {
+            table[first][second] = preflop_index(first as u8, second as u8);
+            second += 1;
+        }
+        first += 1;
+    }
+    table
+};
+
+/// Canonical hole cards of every pair of hole cards, by deck index, computed
+/// at compile time.
+#[cfg(feature = "tables")]
+static PREFLOP_CANONICAL: [[[Card; 2]; 52]; 52] = {
+    let mut table = [[[crate::CANONICAL_DECK[0]; 2]; 52]; 52];
+    let mut first = 0;
+    while first < 52 {
+        let mut second = 0;
+        while second < 52 {
+            table[first][second] = preflop_canonical(first as u8, second as u8);
+            second += 1;
+        }
+        first += 1;
+    }
+    table
+};
+
+/// Canonical hole cards of a starting hand, looked up in a table.
+#[cfg(feature = "tables")]
+pub(crate) fn canonical_hole(first: Card, second: Card) -> [Card; 2] {
+    PREFLOP_CANONICAL[first.deck_index()][second.deck_index()]
+}
+
+// `canonical_index` of two hole cards given by deck index: offsuit hands and
+// pairs come first, as a multiset of two single-card holdings, then suited
+// hands, as one two-card holding
+#[cfg(feature = "tables")]
+const fn preflop_index(first: u8, second: u8) -> u8 {
+    let (low, high) = if first < second {
+        (first / 4, second / 4)
+    } else {
+        (second / 4, first / 4)
+    };
+    if first % 4 == second % 4 {
+        91 + low + high * high.saturating_sub(1) / 2
+    } else {
+        low + high * (high + 1) / 2
+    }
+}
+
+// the lower card takes clubs, and the higher card clubs if suited, else diamonds
+#[cfg(feature = "tables")]
+const fn preflop_canonical(first: u8, second: u8) -> [Card; 2] {
+    let (low, high) = if first < second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let high_suit = if low % 4 == high % 4 { 0 } else { 1 };
+    [
+        crate::CANONICAL_DECK[(low / 4 * 4) as usize],
+        crate::CANONICAL_DECK[(high / 4 * 4 + high_suit) as usize],
+    ]
+}
+
+/// The set of `len` ranks with the given `subset_index`.
+fn subset_from_index(len: u64, mut index: u64) -> u16 {
+    let mut mask = 0;
+    for seen in (1..=len).rev() {
+        let rank = (0..RANKS)
+            .rev()
+            .find(|rank| choose(*rank, seen) <= index)
+            .unwrap();
+        index -= choose(rank, seen);
+        mask |= 1 << rank;
+    }
+    mask
+}
+
+/// Pack the ranks of `mask` into the positions left free by `taken`.
+fn compress(mask: u16, taken: u16) -> u16 {
+    let mut packed = 0;
+    let mut position = 0;
+    for rank in 0..RANKS {
+        if taken >> rank & 1 == 0 {
+            packed |= (mask >> rank & 1) << position;
+            position += 1;
+        }
+    }
+    packed
+}
+
+/// Undo `compress`, spreading packed ranks over the positions left free by `taken`.
+fn expand(packed: u16, taken: u16) -> u16 {
+    let mut mask = 0;
+    let mut position = 0;
+    for rank in 0..RANKS {
+        if taken >> rank & 1 == 0 {
+            mask |= (packed >> position & 1) << rank;
+            position += 1;
+        }
+    }
+    mask
+}
+
+fn cards_of_mask(mask: u16, suit: Suit) -> impl Iterator<Item = Card> {
+    (0..RANKS as u8)
+        .filter(move |rank| mask >> rank & 1 == 1)
+        .map(move |rank| Value::from_u8(rank + 2).unwrap().of(suit))
+}
+
+const fn choose(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let mut acc: u128 = 1;
+    let mut i = 0;
+    while i < k {
+        acc = acc * (n - i) as u128 / (i + 1) as u128;
+        i += 1;
+    }
+    acc as u64
+}
+
+/// Number of multisets of `k` items drawn from `n`.
+const fn multichoose(n: u64, k: u64) -> u64 {
+    if n == 0 {
+        return (k == 0) as u64;
+    }
+    choose(n + k - 1, k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "tables")]
+    #[test]
+    fn test_tables_match_computation() {
+        for mask in 0..1 << RANKS {
+            assert_eq!(subset_index(mask), compute_subset_index(mask));
+        }
+        for first in crate::CANONICAL_DECK.iter() {
+            for second in crate::CANONICAL_DECK.iter() {
+                let hand = [*first, *second];
+                assert_eq!(
+                    canonical_hole(*first, *second).to_vec(),
+                    crate::canonicalize_hand_with_permutation(hand.to_vec()).0
+                );
+                if first != second {
+                    assert_eq!(
+                        canonical_index(&hand),
+                        compute_canonical_index(&hand, Street::Preflop)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_index_pinned() {
+        // persisted tables rely on these, so any renumbering must be deliberate
+        let pinned = [
+            ("3c2d", 1),
+            ("AcAd", 90),
+            ("2c3c", 91),
+            ("AsKs", 168),
+            ("AsKsQs2h7d", 1_151_152),
+            ("7h2c9d9sTd", 15_672),
+            ("AsKsQs2h7d3c", 11_926_171),
+            ("7h2c9d9sTd4h", 1_501_520),
+            ("AsKsQs2h7d3c8s", 115_031_784),
+            ("7h2c9d9sTd4hJh", 38_897_164),
+        ];
+        for (code, index) in pinned.iter() {
+            let hand: Vec<Card> = (0..code.len())
+                .step_by(2)
+                .map(|i| code[i..i + 2].parse().unwrap())
+                .collect();
+            assert_eq!(canonical_index(&hand), *index, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_canonical_index_spec() {
+        let spec = include_str!("../spec/canonical_index.txt");
+        let mut pinned = 0;
+        for line in spec.lines().filter(|line| !line.starts_with('#')) {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let street = match fields[0] {
+                "Preflop" => Street::Preflop,
+                "Flop" => Street::Flop,
+                "Turn" => Street::Turn,
+                "River" => Street::River,
+                other => panic!("unknown street {}", other),
+            };
+            let hand: Vec<Card> = (0..fields[1].len())
+                .step_by(2)
+                .map(|i| fields[1][i..i + 2].parse().unwrap())
+                .collect();
+            let index: u64 = fields[2].parse().unwrap();
+
+            assert_eq!(canonical_index(&hand), index, "{}", line);
+            assert_eq!(hand_from_index(street, index).to_vec(), hand, "{}", line);
+            pinned += 1;
+        }
+        assert_eq!(pinned, 100);
+    }
+
+    #[test]
+    fn test_index_migration_map() {
+        assert_eq!(
+            index_migration_map(Street::Preflop, canonical_index),
+            (0..169).collect::<Vec<_>>()
+        );
+
+        // a scheme numbering the classes backwards migrates by reflection
+        let reversed = index_migration_map(Street::Preflop, |hand| 168 - canonical_index(hand));
+        assert_eq!(reversed, (0..169).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range or repeated")]
+    fn test_index_migration_map_rejects_collisions() {
+        index_migration_map(Street::Preflop, |hand| canonical_index(hand) / 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "hand must not repeat a card")]
+    fn test_canonical_index_rejects_repeated_hole_card() {
+        let ace = crate::Value::Ace.of(Suit::Spades);
+        canonical_index(&[ace, ace]);
+    }
+
+    #[test]
+    #[should_panic(expected = "hand must not repeat a card")]
+    fn test_canonical_index_rejects_card_in_hole_and_board() {
+        let hand: Vec<Card> = ["As", "Kh", "As", "2c", "3c"]
+            .iter()
+            .map(|card| card.parse().unwrap())
+            .collect();
+        canonical_index(&hand);
+    }
+
+    #[test]
+    fn test_layouts_cover_canonical_count() {
+        for street in [Street::Preflop, Street::Flop, Street::Turn, Street::River] {
+            let last = layouts(street).last().unwrap();
+            assert_eq!(last.offset + last.count, street.canonical_count());
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Diagnostics for the cost of canonicalization, behind the `instrumentation` feature.
+
+use crate::{canonicalize_hand, Card};
+use std::cell::Cell;
+
+thread_local! {
+    static ITERATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+pub(crate) fn record_iteration() {
+    ITERATIONS.with(|iterations| iterations.set(iterations.get() + 1));
+}
+
+/// Upper bound on `canonicalize_hand_iterations` for a hand of `len` cards.
+///
+/// One pass assigns suits card by card, and each of the at most four suits
+/// that needs a tie broken costs at most one more pass over the hand.
+pub const fn max_iterations(len: usize) -> usize {
+    5 * len
+}
+
+/// Canonicalize a hand, counting the card visits made after sorting it.
+///
+/// This covers suit assignment and the scans resolving ambiguous suits, which
+/// is everything `canonicalize_hand` does beyond its two sorts.
+pub fn canonicalize_hand_iterations(cards: Vec<Card>) -> (Vec<Card>, usize) {
+    ITERATIONS.with(|iterations| iterations.set(0));
+    let canonical = canonicalize_hand(cards);
+    (canonical, ITERATIONS.with(Cell::get))
+}
@@ -0,0 +1,54 @@
+use crate::{canonicalize_zones, Card, ZoneSpec};
+
+/// A card from a deck which may include jokers.
+///
+/// Jokers have no value or suit, and sort after every card.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CardOrJoker {
+    Card(Card),
+    Joker,
+}
+
+impl From<Card> for CardOrJoker {
+    fn from(card: Card) -> Self {
+        CardOrJoker::Card(card)
+    }
+}
+
+/// Get strategically equivalent hand with lexicographic minimum, for decks with jokers
+///
+/// The first two entries are the hole cards and the remainder is the board;
+/// each part is sorted independently, so jokers come last within it. Jokers
+/// carry no suit, so relabelling suits leaves them alone and the other cards
+/// canonicalize as if the jokers weren't there.
+pub fn canonicalize_hand_with_jokers(cards: Vec<CardOrJoker>) -> Vec<CardOrJoker> {
+    let (hole, board) = cards.split_at(cards.len().min(2));
+    let real = |part: &[CardOrJoker]| -> Vec<Card> {
+        part.iter()
+            .filter_map(|card| match card {
+                CardOrJoker::Card(card) => Some(*card),
+                CardOrJoker::Joker => None,
+            })
+            .collect()
+    };
+    let (real_hole, real_board) = (real(hole), real(board));
+
+    let mut canonical: Vec<Card> = real_hole.iter().chain(&real_board).copied().collect();
+    let zones = [
+        ZoneSpec::unordered(real_hole.len()),
+        ZoneSpec::unordered(real_board.len()),
+    ];
+    canonicalize_zones(&mut canonical, &zones);
+
+    // put the jokers back at the end of the part they came from
+    let mut result: Vec<CardOrJoker> = Vec::with_capacity(cards.len());
+    let (canonical_hole, canonical_board) = canonical.split_at(real_hole.len());
+    for (canonical_part, part) in [(canonical_hole, hole), (canonical_board, board)] {
+        result.extend(canonical_part.iter().copied().map(CardOrJoker::Card));
+        result.resize(
+            result.len() + part.len() - canonical_part.len(),
+            CardOrJoker::Joker,
+        );
+    }
+    result
+}
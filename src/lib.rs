@@ -1,54 +1,179 @@
-#![feature(is_sorted)]
-#![feature(option_result_contains)]
-#![feature(bool_to_option)]
-#![feature(array_map)]
-
 use std::convert::TryInto;
 use strum::IntoEnumIterator;
 
+mod batch;
+pub mod blackjack;
+mod cache;
+mod canonical_key;
+mod canonicalize_ext;
+mod canonicalizer;
+mod card_set;
 mod cards;
+mod combinations;
+mod enumeration;
+mod error;
+mod eval;
+mod hand;
+mod hand_columns;
+mod index;
+#[cfg(feature = "instrumentation")]
+pub mod instrumentation;
+mod jokers;
+pub mod prelude;
+#[cfg(feature = "rand")]
+mod sampling;
+pub mod short_code;
+pub mod snapshot;
+pub mod stats;
 mod suit_map;
+mod suit_permutation;
+pub mod testing;
+pub mod tricks;
+mod value_order;
+mod zones;
 
+pub use batch::*;
+pub use cache::*;
+pub use canonical_key::*;
+pub use canonicalize_ext::*;
+pub use canonicalizer::*;
+pub use card_set::*;
 pub use cards::*;
+pub use combinations::*;
+pub use enumeration::*;
+pub use error::*;
+pub use eval::*;
+pub use hand::*;
+pub use hand_columns::*;
+pub use index::*;
+pub use jokers::*;
+#[cfg(feature = "rand")]
+pub use sampling::*;
 use suit_map::*;
+pub use suit_permutation::*;
+pub use value_order::*;
+pub use zones::*;
 
 /// Permute cards to a new suit variation
+fn permute_suits(mut cards: Vec<Card>, permutation: SuitPermutation) -> Vec<Card> {
+    permute_suits_in_place(&mut cards, permutation);
+    cards
+}
+
+fn permute_suits_in_place(cards: &mut [Card], permutation: SuitPermutation) {
+    for card in cards {
+        *card = permutation.apply(*card);
+    }
+}
+
+/// Get strategically equivalent hand with lexicographic minimum
 ///
-/// Returns a new SuitPermutation from the underlying targets.
+/// The first two cards are the hole cards and the remainder is the board;
+/// each part is sorted independently.
 ///
-/// target_suits is interpreted as the suit the corresponding original
-/// suit should be output as, where the "starting order" is
-///      [Clubs, Diamonds, Hearts, Spades]
+/// Suits are relabelled in the order they are first needed. When two or more
+/// suits cannot be told apart by any later card (for example the two suits of
+/// a pocket pair on a board neither suit appears on) they are assigned in
+/// `Suit` enum order. Every such choice produces the same canonical hand, so
+/// this tie-break never affects the result, only which relabelling is used
+/// internally to reach it.
 ///
-/// e.g. [Hearts, Diamonds, Spades, Clubs] implies
-///   Clubs => Hearts
-///   Diamonds => Diamonds
-///   Hearts => Spades,
-///   Spades => Clubs
-fn permute_suits(mut cards: Vec<Card>, target_suits: SuitMap<Suit>) -> Vec<Card> {
-    let mut seen_targets = [false; 4];
-    for (_, target) in target_suits.iter() {
-        seen_targets[*target as usize] = true;
-    }
-    assert!(
-        seen_targets.iter().all(|seen| *seen),
-        "target_suits must contain all four suits"
-    );
+/// Runs in O(n log n) time for a hand of n cards. Sorting dominates: the
+/// suits are then assigned in a single pass, and each of the at most four
+/// suits whose assignment is ambiguous costs at most one further scan of the
+/// board, however many pairs or runs of suited cards the board contains.
+///
+/// Panics if there are fewer than two cards; see `try_canonicalize_hand` for
+/// a version which validates its input.
+pub fn canonicalize_hand(mut cards: Vec<Card>) -> Vec<Card> {
+    canonicalize_hand_in_place(&mut cards);
+    cards
+}
 
-    for card in &mut cards {
-        card.suit = *target_suits.get(card.suit);
+/// Canonicalize a hand in place, without allocating
+///
+/// Produces the same result as `canonicalize_hand`, and likewise panics if
+/// there are fewer than two cards. With the `tables` feature, starting hands
+/// are a single table lookup.
+pub fn canonicalize_hand_in_place(cards: &mut [Card]) {
+    #[cfg(feature = "tables")]
+    if let [first, second] = cards {
+        let hole = index::canonical_hole(*first, *second);
+        cards.copy_from_slice(&hole);
+        return;
     }
 
+    canonicalize_hand_in_place_with_permutation(cards);
+}
+
+/// Canonicalize a fixed-size hand, keeping its length in the type
+///
+/// Produces the same result as `canonicalize_hand` without allocating, and
+/// likewise panics if there are fewer than two cards.
+pub fn canonicalize_hand_array<const N: usize>(mut cards: [Card; N]) -> [Card; N] {
+    canonicalize_hand_in_place(&mut cards);
     cards
 }
 
-/// Get strategically equivalent hand with lexicographic minimum
-pub fn canonicalize_hand(mut cards: Vec<Card>) -> Vec<Card> {
+/// Canonicalize a hand, also returning the suit relabelling which was applied
+///
+/// The permutation maps each suit of the original hand to its suit in the
+/// canonical hand, so it can be used to relabel per-suit data or cards dealt
+/// later in the same game consistently with the canonical hand.
+pub fn canonicalize_hand_with_permutation(mut cards: Vec<Card>) -> (Vec<Card>, SuitPermutation) {
+    let permutation = canonicalize_hand_in_place_with_permutation(&mut cards);
+    (cards, permutation)
+}
+
+/// Canonicalize a hand, also returning which original card became each canonical card
+///
+/// The second vector holds, at each position of the canonical hand, the card
+/// of the input hand which was relabelled into it. Hole cards stay in the
+/// first two positions, so this gives a card-by-card correspondence between a
+/// live board and its canonical board, e.g. to display results computed for
+/// the canonical hand against the real cards.
+pub fn canonicalize_hand_with_originals(cards: Vec<Card>) -> (Vec<Card>, Vec<Card>) {
+    let (canonical, permutation) = canonicalize_hand_with_permutation(cards);
+    let inverse = permutation.inverse();
+    let originals = canonical.iter().map(|card| inverse.apply(*card)).collect();
+    (canonical, originals)
+}
+
+/// Get the suit relabelling `canonicalize_hand` would apply, without applying it
+///
+/// This is the permutation returned by `canonicalize_hand_with_permutation`,
+/// for callers who only need to relabel per-suit data of their own: the hand
+/// is sorted once to find it, but never relabelled or sorted again. Never
+/// allocates for hands of up to 52 cards. Panics if there are fewer than two
+/// cards.
+pub fn suit_assignment(cards: &[Card]) -> SuitPermutation {
+    let mut buffer = CANONICAL_DECK;
+    match buffer.get_mut(..cards.len()) {
+        Some(sorted) => {
+            sorted.copy_from_slice(cards);
+            assign_suits(sorted)
+        }
+        None => assign_suits(&mut cards.to_vec()),
+    }
+}
+
+fn canonicalize_hand_in_place_with_permutation(cards: &mut [Card]) -> SuitPermutation {
+    let permutation = assign_suits(cards);
+    permute_suits_in_place(cards, permutation);
+
+    // sort cards again - groups mean the original sort is not guaranteed to be correct any more
+    sort_hand(cards);
+
+    permutation
+}
+
+/// Sort the hand and find the suit relabelling which canonicalizes it.
+fn assign_suits(cards: &mut [Card]) -> SuitPermutation {
     // map from original suit (by index) to assigned suit
     let mut assigned_suits = SuitMap::new_copied(None);
 
     // sort hand cards
-    sort_hand(&mut cards);
+    sort_hand(cards);
 
     // hole is special case: it can either be resolved immediately, or if a
     // double we need to look ahead to determine correct order
@@ -58,8 +183,8 @@ pub fn canonicalize_hand(mut cards: Vec<Card>) -> Vec<Card> {
     }) {
         // Swap the suits in the double if the second card has the first suit intersecting
         // with the cards on the table.
-        if suit == hole[1].suit {
-            hole[1].suit = std::mem::replace(&mut hole[0].suit, suit);
+        if suit == hole[1].suit() {
+            hole.swap(0, 1);
         }
     }
 
@@ -70,12 +195,15 @@ pub fn canonicalize_hand(mut cards: Vec<Card>) -> Vec<Card> {
     };
 
     // Assign suits to hole cards - condition above guarantees that this is correctly ordered
-    *assigned_suits.get_mut(hole[0].suit) = Some(suit_generator());
-    assigned_suits.get_mut(hole[1].suit).get_or_insert_with(|| suit_generator());
+    *assigned_suits.get_mut(hole[0].suit()) = Some(suit_generator());
+    assigned_suits
+        .get_mut(hole[1].suit())
+        .get_or_insert_with(&mut suit_generator);
 
     let mut remaining = &cards[2..];
     while let Some((card, next_remaining)) = remaining.split_first() {
-        while assigned_suits.get(card.suit).is_none() {
+        record_iteration();
+        while assigned_suits.get(card.suit()).is_none() {
             let suit = get_next_suit_to_assign(card, next_remaining, &assigned_suits);
             let assigned = assigned_suits.get_mut(suit);
             assert!(assigned.is_none());
@@ -85,15 +213,221 @@ pub fn canonicalize_hand(mut cards: Vec<Card>) -> Vec<Card> {
         remaining = next_remaining;
     }
 
-    let permutation =
-        assigned_suits.map(|suit| suit.unwrap_or_else(|| suit_generator()));
+    let permutation = assigned_suits.map(|suit| suit.unwrap_or_else(&mut suit_generator));
+    SuitPermutation::from(permutation)
+}
 
-    cards = permute_suits(cards, permutation);
+/// Fail with the first card which appears more than once
+pub(crate) fn check_no_duplicates(cards: &[Card]) -> Result<CardSet, CanonicalizeError> {
+    let mut seen = CardSet::new();
+    for card in cards {
+        if !seen.insert(*card) {
+            return Err(CanonicalizeError::DuplicateCard(*card));
+        }
+    }
+    Ok(seen)
+}
 
-    // sort cards again - groups mean the original sort is not guaranteed to be correct any more
-    sort_hand(&mut cards);
+/// Check whether a hand is already in canonical form
+///
+/// Equivalent to `canonicalize_hand(cards.to_vec()) == cards`, but decided
+/// directly in a single pass, without relabelling or sorting, whether or not
+/// the hand is canonical. A hand is canonical exactly when its hole cards and
+/// board are each sorted and every suit holds cards at least as low as the
+/// suits after it: comparing each suit's hole values, then its board values,
+/// from the lowest value up, as `assign_suits` ranks them. Hands with a
+/// repeated card fall back to canonicalizing. Hands with fewer than two cards
+/// have no canonical form and are never canonical.
+pub fn is_canonical(cards: &[Card]) -> bool {
+    if cards.len() < 2 || !cards[0..2].is_sorted() || !cards[2..].is_sorted() {
+        return false;
+    }
 
-    cards
+    let hole: CardSet = cards[..2].iter().copied().collect();
+    let board: CardSet = cards[2..].iter().copied().collect();
+    if hole.len() + board.len() != cards.len() || !hole.is_disjoint(board) {
+        return canonicalize_hand(cards.to_vec()) == cards;
+    }
+
+    // reversed masks put the lowest value first, so a larger key holds lower cards
+    let key = |suit| {
+        (
+            hole.suit_mask(suit).reverse_bits(),
+            board.suit_mask(suit).reverse_bits(),
+        )
+    };
+    Suit::iter()
+        .zip(Suit::iter().skip(1))
+        .all(|(suit, next)| key(suit) >= key(next))
+}
+
+/// Check whether two hands are strategically equivalent
+///
+/// Equivalent to comparing `canonicalize_hand` of both hands, but decided by
+/// matching up the per-suit value masks of the hole and board, so nothing is
+/// sorted or relabelled. Hands with a repeated card fall back to comparing
+/// canonical forms.
+pub fn hands_equivalent(a: &[Card], b: &[Card]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    match (suit_signature(a), suit_signature(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => canonicalize_hand(a.to_vec()) == canonicalize_hand(b.to_vec()),
+    }
+}
+
+/// Hash a hand so that strategically equivalent hands always collide
+///
+/// Hashes the sorted per-suit value masks of the hole and board, which is far
+/// cheaper than canonicalizing. Different classes may share a hash, so use it
+/// to rule out pairs before `hands_equivalent`, not in place of it.
+pub fn iso_hash(cards: &[Card]) -> u64 {
+    let mut masks = [0u32; 4];
+    for (i, card) in cards.iter().enumerate() {
+        let shift = if i < 2 { 16 } else { 0 };
+        masks[card.suit() as usize] |= 1 << (card.value() as u32 - 2 + shift);
+    }
+    masks.sort_unstable();
+
+    // a multiply-rotate mix of the lanes, seeded with the hand length
+    masks.iter().fold(cards.len() as u64, |hash, mask| {
+        (hash ^ *mask as u64)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            .rotate_left(29)
+    })
+}
+
+/// Find a suit relabelling which maps hand `a` onto hand `b`
+///
+/// Returns None if the hands are not strategically equivalent. Otherwise the
+/// permutation maps the suits of `a` to the suits of `b`, so that applying it
+/// to every card of `a` gives the same hole and board as `b` (up to the order
+/// of cards within each).
+pub fn equivalence_witness(a: &[Card], b: &[Card]) -> Option<SuitPermutation> {
+    if a.len() != b.len() {
+        return None;
+    }
+
+    let (canonical_a, to_canonical_a) = canonicalize_hand_with_permutation(a.to_vec());
+    let (canonical_b, to_canonical_b) = canonicalize_hand_with_permutation(b.to_vec());
+    (canonical_a == canonical_b).then(|| to_canonical_b.inverse().compose(&to_canonical_a))
+}
+
+/// Sorted per-suit (hole mask, board mask) pairs, which are equal exactly when two
+/// hands are related by a suit permutation. None if the hand repeats a card, whether
+/// within the hole or board or across them.
+fn suit_signature(cards: &[Card]) -> Option<[u32; 4]> {
+    let (hole, board) = cards.split_at(cards.len().min(2));
+    let hole: CardSet = hole.iter().copied().collect();
+    let board: CardSet = board.iter().copied().collect();
+    if hole.len() + board.len() != cards.len() || !hole.is_disjoint(board) {
+        return None;
+    }
+
+    let mut signature = [0; 4];
+    for (entry, suit) in signature.iter_mut().zip(Suit::iter()) {
+        *entry = (hole.suit_mask(suit) as u32) << 16 | board.suit_mask(suit) as u32;
+    }
+    signature.sort_unstable();
+    Some(signature)
+}
+
+/// Validate a hand, then canonicalize it as `canonicalize_hand` does
+///
+/// Fails if there are fewer than two cards or any card is repeated.
+pub fn try_canonicalize_hand(cards: Vec<Card>) -> Result<Vec<Card>, CanonicalizeError> {
+    if cards.len() < 2 {
+        return Err(CanonicalizeError::TooFewCards(cards.len()));
+    }
+
+    check_no_duplicates(&cards)?;
+    Ok(canonicalize_hand(cards))
+}
+
+/// Get every suit permutation which maps `cards` to its canonical hand
+///
+/// The canonical hand is unique, but when suits are interchangeable in the
+/// original hand several relabellings reach it; `canonicalize_hand` picks only
+/// one of them. The permutations are returned in lexicographic order of their
+/// targets.
+pub fn canonical_permutations(cards: &[Card]) -> Vec<SuitPermutation> {
+    let canonical = canonicalize_hand(cards.to_vec());
+    SuitPermutation::all()
+        .filter(|permutation| {
+            let mut permuted = permute_suits(cards.to_vec(), *permutation);
+            sort_hand(&mut permuted);
+            permuted == canonical
+        })
+        .collect()
+}
+
+/// Iterate every distinct hand which is a suit relabelling of `cards`
+///
+/// Between 1 and 24 hands are produced, each with its hole cards and board
+/// sorted, in lexicographic order; the first is therefore the canonical hand.
+/// Relabellings which give the same hole cards and board are only produced
+/// once. Panics if there are fewer than two cards.
+pub fn isomorphic_variants(cards: &[Card]) -> impl Iterator<Item = Vec<Card>> {
+    let mut variants: Vec<_> = SuitPermutation::all()
+        .map(|permutation| {
+            let mut variant = permute_suits(cards.to_vec(), permutation);
+            sort_hand(&mut variant);
+            variant
+        })
+        .collect();
+    variants.sort_unstable();
+    variants.dedup();
+    variants.into_iter()
+}
+
+/// Count the distinct hands which are suit relabellings of `cards`
+///
+/// This is the number of hands `isomorphic_variants` produces, i.e. 24 divided
+/// by the number of relabellings which leave the hole cards and board
+/// unchanged, and is the weight of the hand's canonical class when counting
+/// hands. It is computed from per-suit masks without relabelling anything.
+pub fn isomorphism_class_size(cards: &[Card]) -> u8 {
+    let signature = match suit_signature(cards) {
+        Some(signature) => signature,
+        None => return isomorphic_variants(cards).count() as u8,
+    };
+
+    // suits with identical holdings can be swapped freely among themselves
+    let automorphisms: u8 = signature
+        .chunk_by(|a, b| a == b)
+        .map(|run| (1..=run.len() as u8).product::<u8>())
+        .product();
+    24 / automorphisms
+}
+
+/// Choose a concrete suit assignment of a board which satisfies `constraint`
+///
+/// Tries every suit relabelling of `board`, starting with the board's own
+/// suits, and returns the first (sorted) variant accepted by `constraint`, or
+/// None if no relabelling satisfies it. Useful for displaying a canonical
+/// board with conventional suits, e.g. requiring a two-tone flop to be in
+/// hearts:
+///
+/// ```
+/// use canonical_hand::{representative_board, Suit::*, Value::*};
+///
+/// let flop = [Two.of(Clubs), Seven.of(Clubs), King.of(Diamonds)];
+/// let shown = representative_board(&flop, |board| {
+///     board.iter().filter(|card| card.suit() == Hearts).count() == 2
+/// });
+/// assert_eq!(shown, Some(vec![Two.of(Hearts), Seven.of(Hearts), King.of(Clubs)]));
+/// ```
+pub fn representative_board(
+    board: &[Card],
+    constraint: impl Fn(&[Card]) -> bool,
+) -> Option<Vec<Card>> {
+    SuitPermutation::all().find_map(|permutation| {
+        let mut permuted = permute_suits(board.to_vec(), permutation);
+        permuted.sort();
+        constraint(&permuted).then_some(permuted)
+    })
 }
 
 #[inline]
@@ -102,38 +436,39 @@ fn get_next_suit_to_assign(
     mut remaining: &[Card],
     assigned_suits: &SuitMap<Option<Suit>>,
 ) -> Suit {
-    assert!(assigned_suits.get(card.suit).is_none());
+    assert!(assigned_suits.get(card.suit()).is_none());
 
     let mut is_ambiguous_group = false;
     let mut ambiguous_group = SuitMap::new_copied(false);
-    *ambiguous_group.get_mut(card.suit) = true;
+    *ambiguous_group.get_mut(card.suit()) = true;
 
     while let Some((next_card, next_remaining)) = remaining.split_first() {
-        if next_card.value != card.value {
+        record_iteration();
+        if next_card.value() != card.value() {
             break;
         }
 
-        if assigned_suits.get(next_card.suit).is_none() {
+        if assigned_suits.get(next_card.suit()).is_none() {
             is_ambiguous_group = true;
-            *ambiguous_group.get_mut(next_card.suit) = true;
+            *ambiguous_group.get_mut(next_card.suit()) = true;
         }
 
         remaining = next_remaining;
     }
 
     if is_ambiguous_group {
-        find_first_intersection(remaining, ambiguous_group).unwrap_or(card.suit)
+        find_first_intersection(remaining, ambiguous_group).unwrap_or(card.suit())
     } else {
-        card.suit
+        card.suit()
     }
 }
 
 #[inline]
 fn hole_cards_same_value(hole: &[Card; 2]) -> Option<SuitMap<bool>> {
-    (hole[0].value == hole[1].value).then(|| {
+    (hole[0].value() == hole[1].value()).then(|| {
         let mut map = SuitMap::new_copied(false);
-        *map.get_mut(hole[0].suit) = true;
-        *map.get_mut(hole[1].suit) = true;
+        *map.get_mut(hole[0].suit()) = true;
+        *map.get_mut(hole[1].suit()) = true;
         map
     })
 }
@@ -153,7 +488,8 @@ fn find_first_intersection(remaining: &[Card], mut suits: SuitMap<bool>) -> Opti
     let mut group_value = None;
 
     for card in remaining {
-        if group_value.is_some() && !group_value.contains(&card.value) {
+        record_iteration();
+        if group_value.is_some() && group_value != Some(card.value()) {
             // The intersecting group has ended
             if group.iter().filter(|(_, is_present)| **is_present).count() > 1 {
                 // But it's still ambiguous, reset to this subset and continue
@@ -166,9 +502,9 @@ fn find_first_intersection(remaining: &[Card], mut suits: SuitMap<bool>) -> Opti
             }
         }
 
-        if *suits.get(card.suit) {
-            group_value = Some(card.value);
-            *group.get_mut(card.suit) = true;
+        if *suits.get(card.suit()) {
+            group_value = Some(card.value());
+            *group.get_mut(card.suit()) = true;
         }
     }
 
@@ -178,6 +514,12 @@ fn find_first_intersection(remaining: &[Card], mut suits: SuitMap<bool>) -> Opti
     found_suit
 }
 
+#[inline]
+fn record_iteration() {
+    #[cfg(feature = "instrumentation")]
+    instrumentation::record_iteration();
+}
+
 #[inline]
 fn sort_hand(hand: &mut [Card]) {
     // sort hole cards
@@ -212,6 +554,90 @@ mod tests {
         assert!(deduped.len() == CANONICAL_DECK.len());
     }
 
+    #[test]
+    fn deck_index_matches_canonical_deck() {
+        for (index, card) in CANONICAL_DECK.iter().enumerate() {
+            assert_eq!(card.deck_index(), index);
+            assert_eq!(card.index() as usize, index);
+            assert_eq!(Card::from_index(index as u8), Some(*card));
+            assert!(cards_of_value(card.value()).contains(card));
+            assert!(cards_of_suit(card.suit()).contains(card));
+        }
+        assert!(cards_of_suit(Hearts).is_sorted());
+        assert_eq!(Card::from_index(52), None);
+    }
+
+    #[test]
+    fn card_is_one_byte() {
+        assert_eq!(std::mem::size_of::<Card>(), 1);
+        assert_eq!(std::mem::size_of::<Option<Card>>(), 1);
+        for value in Value::iter() {
+            for suit in Suit::iter() {
+                let card = value.of(suit);
+                assert_eq!((card.value(), card.suit()), (value, suit));
+                assert_eq!(card.with_suit(Clubs), value.of(Clubs));
+            }
+        }
+    }
+
+    #[test]
+    fn cactus_kev_encoding() {
+        assert_eq!(King.of(Diamonds).to_cactus_kev(), 0x0800_4B25);
+        assert_eq!(Five.of(Spades).to_cactus_kev(), 0x0008_1307);
+        assert_eq!(Jack.of(Clubs).to_cactus_kev(), 0x0200_891D);
+        for card in CANONICAL_DECK.iter() {
+            assert_eq!(Card::from_cactus_kev(card.to_cactus_kev()), Some(*card));
+        }
+        assert_eq!(Card::from_cactus_kev(0), None);
+        assert_eq!(Card::from_cactus_kev(0x0800_4B25 | 0x8000), None);
+    }
+
+    #[test]
+    fn short_deck_is_six_and_up() {
+        let expected: Vec<_> = CANONICAL_DECK
+            .iter()
+            .filter(|card| card.value() >= Six)
+            .copied()
+            .collect();
+        assert_eq!(CANONICAL_SHORT_DECK.to_vec(), expected);
+    }
+
+    #[test]
+    fn packed_encoding_is_pinned() {
+        // the packed encoding is persisted, so it must never change
+        assert_eq!(Two.of(Clubs).to_packed(), 0);
+        assert_eq!(Two.of(Spades).to_packed(), 3);
+        assert_eq!(Ten.of(Diamonds).to_packed(), 33);
+        assert_eq!(Ace.of(Spades).to_packed(), 51);
+        for (byte, card) in CANONICAL_DECK.iter().enumerate() {
+            assert_eq!(card.to_packed() as usize, byte);
+            assert_eq!(Card::from_packed(byte as u8), Some(*card));
+        }
+        assert_eq!(Card::from_packed(52), None);
+        assert_eq!(Card::from_packed(255), None);
+    }
+
+    #[test]
+    fn value_and_suit_from_char() {
+        const ACE_OF_SPADES: Option<Card> = match (Value::from_char('A'), Suit::from_char('s')) {
+            (Some(value), Some(suit)) => Some(value.of(suit)),
+            _ => None,
+        };
+        assert_eq!(ACE_OF_SPADES, Some(Ace.of(Spades)));
+
+        let values: Vec<_> = "23456789TJQKA".chars().map(Value::from_char).collect();
+        let expected: Vec<_> = Value::iter().map(Some).collect();
+        assert_eq!(values, expected);
+        assert_eq!(Value::from_char('t'), Some(Ten));
+        assert_eq!(Value::from_char('1'), None);
+
+        let suits: Vec<_> = "cdhs".chars().map(Suit::from_char).collect();
+        let expected: Vec<_> = Suit::iter().map(Some).collect();
+        assert_eq!(suits, expected);
+        assert_eq!(Suit::from_char('H'), Some(Hearts));
+        assert_eq!(Suit::from_char('x'), None);
+    }
+
     // proptesting strategies
 
     fn any_card() -> impl Strategy<Value = Card> {
@@ -219,16 +645,27 @@ mod tests {
             Two as usize..=Ace as usize,
             Diamonds as usize..=Spades as usize,
         )
-            .prop_map(|(value, suit)| Card {
-                value: Value::from_usize(value).unwrap(),
-                suit: Suit::from_usize(suit).unwrap(),
+            .prop_map(|(value, suit)| {
+                Value::from_usize(value)
+                    .unwrap()
+                    .of(Suit::from_usize(suit).unwrap())
             })
     }
 
-    fn any_suit_permutation() -> impl Strategy<Value = SuitMap<Suit>> {
+    #[test]
+    fn all_suit_permutations_distinct() {
+        let permutations: Vec<_> = all_suit_permutations().collect();
+        assert_eq!(permutations.len(), 24);
+        assert_eq!(permutations[0], [Clubs, Diamonds, Hearts, Spades].into());
+        for (i, permutation) in permutations.iter().enumerate() {
+            assert!(!permutations[..i].contains(permutation));
+        }
+    }
+
+    fn any_suit_permutation() -> impl Strategy<Value = SuitPermutation> {
         Just([Clubs, Diamonds, Hearts, Spades])
             .prop_shuffle()
-            .prop_map(Into::into)
+            .prop_map(|targets| SuitPermutation::new(targets).unwrap())
     }
 
     // proptests for permute_suits
@@ -244,8 +681,8 @@ mod tests {
             let mut original_suit_counts = HashMap::new();
             let mut original_value_counts = HashMap::new();
             for card in &cards {
-                *original_suit_counts.entry(card.suit).or_insert(0) += 1;
-                *original_value_counts.entry(card.value).or_insert(0) += 1;
+                *original_suit_counts.entry(card.suit()).or_insert(0) += 1;
+                *original_value_counts.entry(card.value()).or_insert(0) += 1;
             }
 
             let permuted_cards = permute_suits(cards, permutation);
@@ -253,13 +690,13 @@ mod tests {
             let mut permuted_suit_counts = HashMap::new();
             let mut permuted_value_counts = HashMap::new();
             for card in &permuted_cards {
-                *permuted_suit_counts.entry(card.suit).or_insert(0) += 1;
-                *permuted_value_counts.entry(card.value).or_insert(0) += 1;
+                *permuted_suit_counts.entry(card.suit()).or_insert(0) += 1;
+                *permuted_value_counts.entry(card.value()).or_insert(0) += 1;
             }
 
             assert_eq!(original_value_counts, permuted_value_counts);
             for (suit, count) in original_suit_counts {
-                assert_eq!(count, permuted_suit_counts[permutation.get(suit)]);
+                assert_eq!(count, permuted_suit_counts[&permutation.get(suit)]);
             }
         }
 
@@ -287,6 +724,18 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn test_apply_to_set_matches_per_card(
+            cards in prop::collection::vec(any_card(), 0..=52),
+            permutation in any_suit_permutation(),
+        ) {
+            let set: CardSet = cards.iter().copied().collect();
+            let expected: CardSet = cards.iter().map(|card| permutation.apply(*card)).collect();
+            assert_eq!(permutation.apply_to_set(set), expected);
+        }
+    }
+
     // proptests for canonicalize_hand
 
     prop_compose! {
@@ -327,6 +776,1355 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn test_canonical_permutations_reach_canonical_hand(
+            hand in any_hand(),
+        ) {
+            let canonical = canonicalize_hand(hand.clone());
+            let permutations = canonical_permutations(&hand);
+            assert!(!permutations.is_empty());
+            assert_eq!(24 % permutations.len(), 0);
+            for permutation in permutations {
+                let mut permuted: Vec<_> = hand.iter().map(|card| permutation.apply(*card)).collect();
+                sort_hand(&mut permuted);
+                assert_eq!(permuted, canonical);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_is_canonical(
+            hand in any_hand(),
+        ) {
+            let canonical = canonicalize_hand(hand.clone());
+            assert!(is_canonical(&canonical));
+            assert_eq!(is_canonical(&hand), hand == canonical);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_hands_equivalent(
+            a in any_hand(),
+            b in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical_a = canonicalize_hand(a.clone());
+            assert_eq!(hands_equivalent(&a, &b), canonical_a == canonicalize_hand(b.clone()));
+            assert!(hands_equivalent(&a, &permute_suits(a.clone(), permutation)));
+            assert!(hands_equivalent(&a, &canonical_a));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_equivalence_witness(
+            a in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            // reorder cards within each zone, which must not matter
+            let mut b = permute_suits(a.clone(), permutation);
+            b[..2].reverse();
+            b[2..].reverse();
+
+            let witness = equivalence_witness(&a, &b).unwrap();
+            let mut mapped = permute_suits(a.clone(), witness);
+            sort_hand(&mut mapped);
+            sort_hand(&mut b);
+            assert_eq!(mapped, b);
+        }
+    }
+
+    #[test]
+    fn test_equivalence_witness_inequivalent() {
+        assert_eq!(
+            equivalence_witness(
+                &[Ace.of(Spades), King.of(Spades)],
+                &[Ace.of(Spades), King.of(Hearts)]
+            ),
+            None
+        );
+        assert_eq!(
+            equivalence_witness(&[Ace.of(Spades), King.of(Spades)], &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hands_equivalent_zones() {
+        // same cards, but a different card is in the hole
+        assert!(!hands_equivalent(
+            &[Ace.of(Spades), King.of(Spades), Two.of(Hearts)],
+            &[Ace.of(Spades), Two.of(Hearts), King.of(Spades)],
+        ));
+        // duplicated cards fall back to canonicalization, within or across zones
+        assert!(hands_equivalent(
+            &[Ace.of(Spades), Ace.of(Spades)],
+            &[Ace.of(Hearts), Ace.of(Hearts)],
+        ));
+        assert!(suit_signature(&[Ace.of(Spades), King.of(Spades), Ace.of(Spades)]).is_none());
+        let a = [Ace.of(Spades), King.of(Hearts), Ace.of(Spades)];
+        let b = [Ace.of(Hearts), King.of(Spades), Ace.of(Hearts)];
+        assert_eq!(
+            hands_equivalent(&a, &b),
+            canonicalize_hand(a.to_vec()) == canonicalize_hand(b.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_is_canonical_exhaustive() {
+        // every sorted hand of two hole cards and a two-card board, plus the hard cases
+        let mut checked = 0;
+        for hole in combinations(&CANONICAL_DECK, 2) {
+            let dead: CardSet = hole.iter().copied().collect();
+            for board in combinations_with_dead(dead, 2) {
+                let mut hand = hole.clone();
+                hand.extend(board.to_vec());
+                let expected = canonicalize_hand(hand.clone()) == hand;
+                assert_eq!(is_canonical(&hand), expected, "{:?}", hand);
+                checked += expected as usize;
+            }
+        }
+        assert_eq!(checked, 93769);
+
+        for hand in adversarial_hands() {
+            let canonical = canonicalize_hand(hand.clone());
+            assert!(is_canonical(&canonical), "{:?}", canonical);
+            let mut sorted = hand.clone();
+            sort_hand(&mut sorted);
+            assert_eq!(is_canonical(&sorted), sorted == canonical);
+        }
+    }
+
+    #[test]
+    fn test_is_canonical_short_hands() {
+        assert!(!is_canonical(&[]));
+        assert!(!is_canonical(&[Two.of(Clubs)]));
+        assert!(is_canonical(&[Two.of(Clubs), Two.of(Diamonds)]));
+        assert!(!is_canonical(&[Two.of(Diamonds), Two.of(Clubs)]));
+    }
+
+    #[test]
+    fn test_canonicalize_hand_in_place() {
+        let mut hand = [
+            King.of(Hearts),
+            Two.of(Spades),
+            Two.of(Hearts),
+            Nine.of(Diamonds),
+            Three.of(Spades),
+        ];
+        canonicalize_hand_in_place(&mut hand);
+        assert_eq!(
+            hand,
+            [
+                Two.of(Clubs),
+                King.of(Diamonds),
+                Two.of(Diamonds),
+                Three.of(Clubs),
+                Nine.of(Hearts),
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_array(
+            hand in any_hand(),
+        ) {
+            let canonical = canonicalize_hand(hand.clone());
+            match hand.len() {
+                2 => assert_eq!(canonicalize_hand_array::<2>(hand.try_into().unwrap()).to_vec(), canonical),
+                5 => assert_eq!(canonicalize_hand_array::<5>(hand.try_into().unwrap()).to_vec(), canonical),
+                6 => assert_eq!(canonicalize_hand_array::<6>(hand.try_into().unwrap()).to_vec(), canonical),
+                7 => assert_eq!(canonicalize_hand_array::<7>(hand.try_into().unwrap()).to_vec(), canonical),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_canonicalize_hand_errors() {
+        assert_eq!(
+            try_canonicalize_hand(vec![]),
+            Err(CanonicalizeError::TooFewCards(0))
+        );
+        assert_eq!(
+            try_canonicalize_hand(vec![Ace.of(Spades)]),
+            Err(CanonicalizeError::TooFewCards(1))
+        );
+        assert_eq!(
+            try_canonicalize_hand(vec![Ace.of(Spades), Two.of(Clubs), Ace.of(Spades)]),
+            Err(CanonicalizeError::DuplicateCard(Ace.of(Spades)))
+        );
+        assert_eq!(
+            try_canonicalize_hand(vec![Ace.of(Spades), Two.of(Clubs)]),
+            Ok(vec![Two.of(Clubs), Ace.of(Diamonds)])
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_permutation(
+            hand in any_hand(),
+        ) {
+            let (canonical, permutation) = canonicalize_hand_with_permutation(hand.clone());
+            assert_eq!(canonical, canonicalize_hand(hand.clone()));
+            let mut permuted: Vec<_> = hand.iter().map(|card| permutation.apply(*card)).collect();
+            sort_hand(&mut permuted);
+            assert_eq!(permuted, canonical);
+        }
+    }
+
+    #[test]
+    fn test_canonical_permutations_pocket_pair() {
+        // the pair's suits are interchangeable, as are the two unused suits
+        let permutations = canonical_permutations(&[Ace.of(Hearts), Ace.of(Spades)]);
+        assert_eq!(permutations.len(), 4);
+        for permutation in permutations {
+            assert!(permutation.get(Hearts) <= Diamonds);
+            assert!(permutation.get(Spades) <= Diamonds);
+        }
+    }
+
+    // proptests for zone canonicalization
+
+    prop_compose! {
+        fn any_omaha_hand()(
+            shuffled_deck in Just(CANONICAL_DECK.to_vec()).prop_shuffle(),
+            dealt_cards in prop::sample::select(&[4, 7, 8, 9][..]),
+        ) -> Vec<Card> {
+            shuffled_deck[0..dealt_cards].to_vec()
+        }
+    }
+
+    /// Hands which keep several suits interchangeable for as long as possible.
+    fn adversarial_hands() -> Vec<Vec<Card>> {
+        let values: Vec<_> = Value::iter().collect();
+        let suits: Vec<_> = Suit::iter().collect();
+        let every = |suits: &[Suit]| -> Vec<Card> {
+            values
+                .iter()
+                .flat_map(|value| suits.iter().map(move |suit| value.of(*suit)))
+                .collect()
+        };
+
+        let mut hands = vec![
+            // every pair, trips and quads
+            every(&suits[..2]),
+            every(&suits[..3]),
+            every(&suits),
+            // a monotone run, with the hole cards in another suit
+            std::iter::once(Two.of(Hearts))
+                .chain(std::iter::once(Three.of(Hearts)))
+                .chain(cards_of_suit(Clubs).iter().copied())
+                .collect(),
+        ];
+        // pocket pairs whose suits only separate on the last card
+        for n in 2..=12 {
+            let mut hand = vec![Ace.of(Clubs), Ace.of(Diamonds)];
+            hand.extend(
+                values[..n]
+                    .iter()
+                    .flat_map(|value| vec![value.of(Clubs), value.of(Diamonds)]),
+            );
+            hand.push(values[n].of(Clubs));
+            hands.push(hand);
+        }
+        hands
+    }
+
+    #[test]
+    fn test_canonicalize_hand_adversarial() {
+        for hand in adversarial_hands() {
+            let mut expected = hand.clone();
+            let zones = [ZoneSpec::unordered(2), ZoneSpec::unordered(hand.len() - 2)];
+            canonicalize_zones(&mut expected, &zones);
+            assert_eq!(canonicalize_hand(hand), expected);
+        }
+    }
+
+    #[cfg(feature = "instrumentation")]
+    #[test]
+    fn test_canonicalize_hand_iterations_bounded() {
+        use instrumentation::*;
+
+        for hand in adversarial_hands() {
+            let len = hand.len();
+            let (canonical, iterations) = canonicalize_hand_iterations(hand.clone());
+            assert_eq!(canonical, canonicalize_hand(hand));
+            assert!(
+                iterations <= max_iterations(len),
+                "{} iterations for {} cards",
+                iterations,
+                len
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_order(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            assert_eq!(canonicalize_hand_with_order(hand.clone(), ValueOrder::AceHigh), canonicalize_hand(hand.clone()));
+
+            let canonical = canonicalize_hand_with_order(hand.clone(), ValueOrder::AceLow);
+            let by_order = |a: &Card, b: &Card| ValueOrder::AceLow.cmp_cards(a, b);
+            assert!(canonical[..2].is_sorted_by(|a, b| by_order(a, b).is_le()));
+            assert!(canonical[2..].is_sorted_by(|a, b| by_order(a, b).is_le()));
+
+            // it is the minimum over relabellings, comparing with aces low
+            let mut permuted = permute_suits(canonical.clone(), permutation);
+            permuted[..2].sort_by(by_order);
+            permuted[2..].sort_by(by_order);
+            let ordering = canonical.iter().zip(&permuted).map(|(a, b)| by_order(a, b)).find(|ordering| ordering.is_ne());
+            assert_ne!(ordering, Some(std::cmp::Ordering::Greater));
+            assert_eq!(canonicalize_hand_with_order(permuted, ValueOrder::AceLow), canonical);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_hand_ace_low() {
+        let hand = vec![
+            Ace.of(Spades),
+            Two.of(Hearts),
+            Ace.of(Hearts),
+            Three.of(Spades),
+            Four.of(Diamonds),
+        ];
+        assert_eq!(
+            canonicalize_hand_with_order(hand.clone(), ValueOrder::AceLow),
+            vec![
+                Ace.of(Clubs),
+                Two.of(Diamonds),
+                Ace.of(Diamonds),
+                Three.of(Clubs),
+                Four.of(Hearts)
+            ]
+        );
+        assert_eq!(
+            canonicalize_hand(hand),
+            vec![
+                Two.of(Clubs),
+                Ace.of(Diamonds),
+                Three.of(Diamonds),
+                Four.of(Hearts),
+                Ace.of(Clubs)
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_zones_match_canonicalize_hand(
+            hand in any_hand(),
+        ) {
+            let mut canonical = hand.clone();
+            let zones = [ZoneSpec::unordered(2), ZoneSpec::unordered(hand.len() - 2)];
+            canonicalize_zones(&mut canonical, &zones);
+            assert_eq!(canonical, canonicalize_hand(hand));
+        }
+
+        #[test]
+        fn test_canonicalize_omaha_hand_invariant(
+            hand in any_omaha_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical = canonicalize_omaha_hand(hand.clone());
+            assert!(canonical[..4].is_sorted() && canonical[4..].is_sorted());
+            assert_eq!(canonicalize_omaha_hand(canonical.clone()), canonical);
+
+            // relabelling suits and reordering within zones can't change the result
+            let mut permuted = permute_suits(hand, permutation);
+            permuted[..4].reverse();
+            permuted[4..].reverse();
+            assert_eq!(canonicalize_omaha_hand(permuted), canonical);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_hole_count(
+            hand in any_omaha_hand(),
+            hole_count in 0..=4usize,
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical = canonicalize_hand_with_hole_count(hand.clone(), hole_count);
+            assert!(canonical[..hole_count].is_sorted() && canonical[hole_count..].is_sorted());
+            let permuted = permute_suits(hand, permutation);
+            assert_eq!(canonicalize_hand_with_hole_count(permuted, hole_count), canonical);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_holdem_agrees_with_flat_zones(
+            hand in any_hand().prop_filter("needs a flop", |hand| hand.len() >= 5),
+            permutation in any_suit_permutation(),
+        ) {
+            let turn = hand.get(5).copied();
+            let river = hand.get(6).copied();
+            let streets = canonicalize_holdem(
+                [hand[0], hand[1]],
+                [hand[2], hand[3], hand[4]],
+                turn,
+                river,
+            );
+            assert_eq!(streets.turn.is_some(), turn.is_some());
+            assert_eq!(streets.river.is_some(), river.is_some());
+
+            let mut flat = vec![streets.hole[0], streets.hole[1], streets.flop[0], streets.flop[1], streets.flop[2]];
+            flat.extend(streets.turn.iter().chain(streets.river.iter()));
+            // street-structured canonicalization refines the flat board
+            assert_eq!(canonicalize_hand(flat.clone()), canonicalize_hand(hand.clone()));
+
+            let permuted = permute_suits(hand, permutation);
+            let permuted_streets = canonicalize_holdem(
+                [permuted[1], permuted[0]],
+                [permuted[4], permuted[2], permuted[3]],
+                permuted.get(5).copied(),
+                permuted.get(6).copied(),
+            );
+            assert_eq!(permuted_streets, streets);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalizer_matches_canonicalize_holdem(
+            hand in any_hand().prop_filter("needs a flop", |hand| hand.len() >= 5),
+        ) {
+            crate::testing::assert_incremental_matches_batch([hand[0], hand[1]], &hand[2..]);
+
+            let mut canonicalizer = Canonicalizer::new();
+            canonicalizer.push_hole([hand[0], hand[1]]);
+            let hole = canonicalizer.canonical().to_vec();
+            canonicalizer.push_flop([hand[2], hand[3], hand[4]]);
+            for card in &hand[5..] {
+                let previous = canonicalizer.permutation();
+                let previous_canonical = canonicalizer.canonical().to_vec();
+                let delta = if previous_canonical.len() == 5 {
+                    canonicalizer.push_turn(*card)
+                } else {
+                    canonicalizer.push_river(*card)
+                };
+
+                assert_eq!(delta.compose(&previous), canonicalizer.permutation());
+                // the delta maps each earlier canonical street onto itself
+                let mut relabelled = permute_suits(previous_canonical.clone(), delta);
+                relabelled[..2].sort();
+                relabelled[2..5].sort();
+                assert_eq!(relabelled, previous_canonical);
+            }
+
+            let streets = canonicalize_holdem(
+                [hand[0], hand[1]],
+                [hand[2], hand[3], hand[4]],
+                hand.get(5).copied(),
+                hand.get(6).copied(),
+            );
+            let canonical = canonicalizer.canonical();
+            assert_eq!(canonical[..2], hole[..]);
+            assert_eq!(canonical[..2], streets.hole);
+            assert_eq!(canonical[2..5], streets.flop);
+            assert_eq!(canonical.get(5).copied(), streets.turn);
+            assert_eq!(canonical.get(6).copied(), streets.river);
+
+            for permutation in canonicalizer.permutations() {
+                let mut permuted = permute_suits(hand.clone(), *permutation);
+                permuted[..2].sort();
+                permuted[2..5].sort();
+                assert_eq!(permuted, canonical);
+            }
+        }
+    }
+
+    prop_compose! {
+        fn any_multiway_deal()(
+            shuffled_deck in Just(CANONICAL_DECK.to_vec()).prop_shuffle(),
+            players in 1..=6usize,
+            board_size in prop::sample::select(&[0, 3, 4, 5][..]),
+        ) -> (Vec<[Card; 2]>, Vec<Card>) {
+            let holes = shuffled_deck.chunks_exact(2).take(players).map(|hole| [hole[0], hole[1]]).collect();
+            let board = shuffled_deck[2 * players..2 * players + board_size].to_vec();
+            (holes, board)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_multiway(
+            (holes, board) in any_multiway_deal(),
+            permutation in any_suit_permutation(),
+        ) {
+            let (canonical_holes, canonical_board) = canonicalize_multiway(&holes, &board);
+            assert_eq!(canonical_holes.len(), holes.len());
+            assert!(canonical_board.is_sorted());
+
+            // the first player's view matches single-hand canonicalization
+            let mut first_player = holes[0].to_vec();
+            first_player.extend_from_slice(&board);
+            let mut canonical_first = canonical_holes[0].to_vec();
+            canonical_first.extend_from_slice(&canonical_board);
+            assert_eq!(canonicalize_hand(first_player), canonicalize_hand(canonical_first));
+
+            let permuted_holes: Vec<_> = holes.iter().map(|hole| [permutation.apply(hole[1]), permutation.apply(hole[0])]).collect();
+            let permuted_board = permute_suits(board.clone(), permutation);
+            assert_eq!(
+                canonicalize_multiway(&permuted_holes, &permuted_board),
+                (canonical_holes, canonical_board)
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_multiway_with_seat_symmetry(
+            (holes, board) in any_multiway_deal(),
+            permutation in any_suit_permutation(),
+        ) {
+            let (canonical_holes, canonical_board) = canonicalize_multiway_with_seat_symmetry(&holes, &board);
+            assert!(canonical_holes[1..].is_sorted());
+
+            // relabelling suits and reseating opponents can't change the result
+            let mut permuted_holes: Vec<_> = holes.iter().map(|hole| [permutation.apply(hole[0]), permutation.apply(hole[1])]).collect();
+            permuted_holes[1..].reverse();
+            let permuted_board = permute_suits(board.clone(), permutation);
+            assert_eq!(
+                canonicalize_multiway_with_seat_symmetry(&permuted_holes, &permuted_board),
+                (canonical_holes.clone(), canonical_board.clone())
+            );
+
+            // and it is never larger than keeping the seats fixed
+            let (seated_holes, seated_board) = canonicalize_multiway(&holes, &board);
+            let mut sorted_seated = seated_holes.clone();
+            sorted_seated[1..].sort();
+            assert!((canonical_holes, canonical_board) <= (sorted_seated, seated_board));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_suit_assignment(hand in any_hand()) {
+            let (_, permutation) = canonicalize_hand_with_permutation(hand.clone());
+            assert_eq!(suit_assignment(&hand), permutation);
+        }
+    }
+
+    #[test]
+    fn test_suit_assignment_large_hand() {
+        let mut deck = CANONICAL_DECK.to_vec();
+        deck.extend_from_slice(&CANONICAL_DECK[..4]);
+        let (_, permutation) = canonicalize_hand_with_permutation(deck.clone());
+        assert_eq!(suit_assignment(&deck), permutation);
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_originals(hand in any_hand()) {
+            let (canonical, originals) = canonicalize_hand_with_originals(hand.clone());
+            assert_eq!(canonical, canonicalize_hand(hand.clone()));
+
+            // originals is a rearrangement of the input which keeps the hole cards apart
+            let mut sorted_hole = hand[..2].to_vec();
+            sorted_hole.sort();
+            let mut original_hole = originals[..2].to_vec();
+            original_hole.sort();
+            assert_eq!(original_hole, sorted_hole);
+            let mut sorted_hand = hand.clone();
+            sorted_hand.sort();
+            let mut sorted_originals = originals.clone();
+            sorted_originals.sort();
+            assert_eq!(sorted_originals, sorted_hand);
+
+            // and one suit relabelling takes every original card to its canonical card
+            let (_, permutation) = canonicalize_hand_with_permutation(hand);
+            for (original, canonical) in originals.iter().zip(&canonical) {
+                assert_eq!(permutation.apply(*original), *canonical);
+            }
+        }
+    }
+
+    prop_compose! {
+        fn any_double_board_deal()(
+            shuffled_deck in Just(CANONICAL_DECK.to_vec()).prop_shuffle(),
+            board_size in prop::sample::select(&[3, 4, 5][..]),
+        ) -> ([Card; 2], Vec<Card>, Vec<Card>) {
+            let hole = [shuffled_deck[0], shuffled_deck[1]];
+            let board_a = shuffled_deck[2..2 + board_size].to_vec();
+            let board_b = shuffled_deck[2 + board_size..2 + 2 * board_size].to_vec();
+            (hole, board_a, board_b)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_double_board(
+            (hole, board_a, board_b) in any_double_board_deal(),
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical = canonicalize_double_board(hole, &board_a, &board_b);
+            assert!(canonical.1.is_sorted());
+            assert!(canonical.2.is_sorted());
+
+            // the first board alone canonicalizes like a normal hand
+            let mut first = hole.to_vec();
+            first.extend_from_slice(&board_a);
+            let mut canonical_first = canonical.0.to_vec();
+            canonical_first.extend_from_slice(&canonical.1);
+            assert_eq!(canonicalize_hand(first), canonicalize_hand(canonical_first));
+
+            let permuted_hole = [permutation.apply(hole[1]), permutation.apply(hole[0])];
+            let permuted_a = permute_suits(board_a.clone(), permutation);
+            let permuted_b = permute_suits(board_b.clone(), permutation);
+            assert_eq!(canonicalize_double_board(permuted_hole, &permuted_a, &permuted_b), canonical);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_double_board_tie_break() {
+        // the first board leaves hearts and spades interchangeable, the second decides
+        let hole = [Ace.of(Clubs), King.of(Clubs)];
+        let board_a = [Two.of(Diamonds), Three.of(Diamonds), Four.of(Diamonds)];
+        let board_b = [Five.of(Spades), Six.of(Hearts), Seven.of(Spades)];
+        let (_, canonical_a, canonical_b) = canonicalize_double_board(hole, &board_a, &board_b);
+        assert_eq!(canonical_a, board_a);
+        assert_eq!(
+            canonical_b,
+            vec![Five.of(Hearts), Six.of(Spades), Seven.of(Hearts)]
+        );
+    }
+
+    prop_compose! {
+        fn any_run_it_twice_deal()(
+            shuffled_deck in Just(CANONICAL_DECK.to_vec()).prop_shuffle(),
+            prefix_size in prop::sample::select(vec![0, 3, 4]),
+        ) -> ([Card; 2], Vec<Card>, Vec<Card>, Vec<Card>) {
+            let runout_size = 5 - prefix_size;
+            let (hole, rest) = shuffled_deck.split_at(2);
+            let (prefix, rest) = rest.split_at(prefix_size);
+            let (first, rest) = rest.split_at(runout_size);
+            ([hole[0], hole[1]], prefix.to_vec(), first.to_vec(), rest[..runout_size].to_vec())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_run_it_twice(
+            (hole, prefix, first, second) in any_run_it_twice_deal(),
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical = canonicalize_run_it_twice(hole, &prefix, [&first, &second]);
+            assert!(canonical.1.is_sorted());
+
+            // the turn and river keep their order: they are relabelled, not sorted
+            let flop_len = if prefix.is_empty() { 3 } else { 0 };
+            let (_, _, [canonical_first, _]) = &canonical;
+            assert!(canonical_first[..flop_len].is_sorted());
+            assert_eq!(
+                canonical_first[flop_len..].iter().map(|card| card.value()).collect::<Vec<_>>(),
+                first[flop_len..].iter().map(|card| card.value()).collect::<Vec<_>>()
+            );
+
+            // but the order of a runout's flop cards doesn't matter
+            let mut reordered_first = first.clone();
+            reordered_first[..flop_len].reverse();
+            assert_eq!(
+                canonicalize_run_it_twice(hole, &prefix, [&reordered_first, &second]),
+                canonical
+            );
+
+            let permuted_hole = [permutation.apply(hole[1]), permutation.apply(hole[0])];
+            let permuted_prefix = permute_suits(prefix.iter().rev().copied().collect(), permutation);
+            let permuted_first = permute_suits(first.clone(), permutation);
+            let permuted_second = permute_suits(second.clone(), permutation);
+            assert_eq!(
+                canonicalize_run_it_twice(permuted_hole, &permuted_prefix, [&permuted_first, &permuted_second]),
+                canonical
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "prefix and runouts must end on a street")]
+    fn test_canonicalize_run_it_twice_rejects_empty_board() {
+        canonicalize_run_it_twice([Ace.of(Spades), King.of(Spades)], &[], [&[], &[]]);
+    }
+
+    prop_compose! {
+        fn any_stud_hand()(
+            shuffled_deck in Just(CANONICAL_DECK.to_vec()).prop_shuffle(),
+            street in 3..=7usize,
+        ) -> (Vec<Card>, Vec<Card>) {
+            // two down-cards, up-cards on 3rd to 6th street, and a final down-card
+            let up_count = street.min(6) - 2;
+            let down_count = if street == 7 { 3 } else { 2 };
+            (shuffled_deck[..down_count].to_vec(), shuffled_deck[down_count..down_count + up_count].to_vec())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_stud(
+            (down, up) in any_stud_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let (canonical_down, canonical_up) = canonicalize_stud(&down, &up);
+            assert!(canonical_down.is_sorted());
+            assert_eq!(
+                canonical_up.iter().map(|card| card.value()).collect::<Vec<_>>(),
+                up.iter().map(|card| card.value()).collect::<Vec<_>>()
+            );
+
+            let permuted_down = permute_suits(down.iter().rev().copied().collect(), permutation);
+            let permuted_up = permute_suits(up.clone(), permutation);
+            assert_eq!(canonicalize_stud(&permuted_down, &permuted_up), (canonical_down, canonical_up));
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_stud_keeps_up_cards_apart() {
+        // the same seven cards with a different card face-up are different hands
+        let first = canonicalize_stud(&[Ace.of(Spades), King.of(Spades)], &[Two.of(Hearts)]);
+        let second = canonicalize_stud(&[Ace.of(Spades), Two.of(Hearts)], &[King.of(Spades)]);
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            (vec![King.of(Clubs), Ace.of(Clubs)], vec![Two.of(Diamonds)])
+        );
+    }
+
+    prop_compose! {
+        fn any_draw_hand()(
+            shuffled_deck in Just(CANONICAL_DECK.to_vec()).prop_shuffle(),
+            discard_count in 0..=15usize,
+        ) -> (Vec<Card>, Vec<Card>) {
+            (shuffled_deck[..5].to_vec(), shuffled_deck[5..5 + discard_count].to_vec())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_draw(
+            (kept, discards) in any_draw_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let (canonical_kept, canonical_discards) = canonicalize_draw(&kept, &discards);
+            assert!(canonical_kept.is_sorted());
+            assert!(canonical_discards.is_sorted());
+            assert_eq!(canonical_discards.len(), discards.len());
+
+            let permuted_kept = permute_suits(kept.iter().rev().copied().collect(), permutation);
+            let permuted_discards = permute_suits(discards.clone(), permutation);
+            assert_eq!(
+                canonicalize_draw(&permuted_kept, &permuted_discards),
+                (canonical_kept, canonical_discards)
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_draw_discards_break_ties() {
+        // two suits are interchangeable in the kept hand until a discard is seen
+        let (kept, discards) = canonicalize_draw(
+            &[
+                Two.of(Hearts),
+                Two.of(Spades),
+                Four.of(Clubs),
+                Five.of(Clubs),
+                Seven.of(Clubs),
+            ],
+            &[Ace.of(Spades), Three.of(Hearts)],
+        );
+        assert_eq!(
+            kept,
+            vec![
+                Two.of(Clubs),
+                Two.of(Diamonds),
+                Four.of(Hearts),
+                Five.of(Hearts),
+                Seven.of(Hearts)
+            ]
+        );
+        assert_eq!(discards, vec![Three.of(Clubs), Ace.of(Diamonds)]);
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_jokers(
+            hand in any_hand(),
+            jokers in prop::collection::vec(any::<prop::sample::Index>(), 0..=2),
+        ) {
+            // without jokers this is plain canonicalization
+            let plain: Vec<CardOrJoker> = hand.iter().copied().map(CardOrJoker::from).collect();
+            let canonical: Vec<CardOrJoker> = canonicalize_hand(hand.clone()).into_iter().map(CardOrJoker::from).collect();
+            assert_eq!(canonicalize_hand_with_jokers(plain.clone()), canonical);
+
+            // jokers replace cards without moving between hole and board
+            let mut with_jokers = plain;
+            for index in &jokers {
+                with_jokers[index.index(hand.len())] = CardOrJoker::Joker;
+            }
+            let canonical = canonicalize_hand_with_jokers(with_jokers.clone());
+            assert!(canonical[..2].is_sorted() && canonical[2..].is_sorted());
+            let count_jokers = |cards: &[CardOrJoker]| cards.iter().filter(|card| **card == CardOrJoker::Joker).count();
+            assert_eq!(count_jokers(&canonical[..2]), count_jokers(&with_jokers[..2]));
+            assert_eq!(count_jokers(&canonical), count_jokers(&with_jokers));
+            assert_eq!(canonicalize_hand_with_jokers(canonical.clone()), canonical);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_hand_with_jokers_ignores_jokers() {
+        use CardOrJoker::Joker;
+        let hand = vec![
+            Joker,
+            Ace.of(Hearts).into(),
+            Joker,
+            King.of(Spades).into(),
+            King.of(Hearts).into(),
+        ];
+        assert_eq!(
+            canonicalize_hand_with_jokers(hand),
+            vec![
+                Ace.of(Clubs).into(),
+                Joker,
+                King.of(Clubs).into(),
+                King.of(Diamonds).into(),
+                Joker
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_pinned_suit(
+            hand in any_hand(),
+            pinned in prop::sample::select(&[Clubs, Diamonds, Hearts, Spades][..]),
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical = canonicalize_hand_with_pinned_suit(hand.clone(), pinned);
+
+            // the pinned suit keeps its cards
+            let pinned_cards = |cards: &[Card]| {
+                let mut cards: Vec<_> = cards.iter().filter(|card| card.suit() == pinned).copied().collect();
+                cards.sort();
+                cards
+            };
+            assert_eq!(pinned_cards(&canonical), pinned_cards(&hand));
+
+            // it is never smaller than the unrestricted canonical hand
+            assert!(canonicalize_hand(hand.clone()) <= canonical);
+
+            // and relabelling the other suits doesn't change it
+            if permutation.get(pinned) == pinned {
+                let permuted = permute_suits(hand, permutation);
+                assert_eq!(canonicalize_hand_with_pinned_suit(permuted, pinned), canonical);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_hand_with_assigned_suits(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+            assigned_count in 0..=4usize,
+        ) {
+            // constraints taken from a real permutation can always be met
+            let assigned: Vec<_> = Suit::iter().take(assigned_count).map(|suit| (suit, permutation.get(suit))).collect();
+            let (canonical, applied) = canonicalize_hand_with_assigned_suits(hand.clone(), &assigned).unwrap();
+            for (original, target) in &assigned {
+                assert_eq!(applied.get(*original), *target);
+            }
+
+            let mut expected = permute_suits(hand.clone(), applied);
+            sort_hand(&mut expected);
+            assert_eq!(canonical, expected);
+            assert!(canonicalize_hand(hand.clone()) <= canonical);
+            if assigned_count == 0 {
+                assert_eq!(canonical, canonicalize_hand(hand));
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_hand_with_contradictory_suits() {
+        let hand = vec![Ace.of(Spades), King.of(Hearts)];
+        assert_eq!(
+            canonicalize_hand_with_assigned_suits(
+                hand.clone(),
+                &[(Spades, Clubs), (Hearts, Clubs)]
+            ),
+            None
+        );
+        assert_eq!(
+            canonicalize_hand_with_assigned_suits(
+                hand.clone(),
+                &[(Spades, Clubs), (Spades, Hearts)]
+            ),
+            None
+        );
+        assert_eq!(
+            canonicalize_hand_with_assigned_suits(hand, &[(Spades, Hearts)])
+                .map(|(canonical, _)| canonical),
+            Some(vec![King.of(Clubs), Ace.of(Hearts)])
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_cards(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let canonical = canonicalize_cards(&hand);
+            assert!(canonical.is_sorted());
+            assert_eq!(canonicalize_cards(&permute_suits(hand.clone(), permutation)), canonical);
+
+            // with no hole cards, everything plays as the board
+            let mut sorted = permute_suits(canonical.clone(), permutation);
+            sorted.sort();
+            assert!(canonical <= sorted);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_cards_short() {
+        assert_eq!(canonicalize_cards(&[]), vec![]);
+        assert_eq!(canonicalize_cards(&[Ace.of(Spades)]), vec![Ace.of(Clubs)]);
+        assert_eq!(canonicalize_cards(&CANONICAL_DECK), CANONICAL_DECK.to_vec());
+    }
+
+    proptest! {
+        #[test]
+        fn test_isomorphic_variants(hand in any_hand()) {
+            let variants: Vec<_> = isomorphic_variants(&hand).collect();
+            assert_eq!(variants[0], canonicalize_hand(hand.clone()));
+            assert!(variants.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(24 % variants.len(), 0);
+            for variant in &variants {
+                assert!(hands_equivalent(variant, &hand));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_isomorphism_class_size(hand in any_hand()) {
+            assert_eq!(isomorphism_class_size(&hand) as usize, isomorphic_variants(&hand).count());
+        }
+    }
+
+    #[test]
+    fn test_isomorphism_class_size_preflop() {
+        // every starting hand is counted once across the 169 classes
+        let total: usize = combinations(&CANONICAL_DECK, 2)
+            .filter(|hand| is_canonical(hand))
+            .map(|hand| isomorphism_class_size(&hand) as usize)
+            .sum();
+        assert_eq!(total, 1326);
+        assert_eq!(isomorphism_class_size(&[Ace.of(Spades), Ace.of(Spades)]), 4);
+    }
+
+    #[test]
+    fn test_enumerate_canonical() {
+        let choose = |n: u64, k: u64| (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1));
+        for (hole_size, board_size, classes) in
+            [(2, 0, 169), (0, 3, 1755), (4, 0, 16432), (2, 2, 93769)]
+        {
+            let mut count = 0;
+            let mut total = 0;
+            for (hand, weight) in enumerate_canonical(hole_size, board_size) {
+                assert_eq!(
+                    canonicalize_hand_with_hole_count(hand.clone(), hole_size),
+                    hand
+                );
+                count += 1;
+                total += weight as u64;
+            }
+            assert_eq!(count, classes);
+            assert_eq!(
+                total,
+                choose(52, hole_size as u64) * choose(52 - hole_size as u64, board_size as u64)
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalize_batch(hands in prop::collection::vec(any_hand(), 0..20)) {
+            let expected: Vec<_> = hands.iter().cloned().map(canonicalize_hand).collect();
+            assert_eq!(canonicalize_batch(&hands), expected);
+            #[cfg(feature = "rayon")]
+            assert_eq!(par_canonicalize_batch(&hands), expected);
+        }
+    }
+
+    #[test]
+    fn test_canonical_histogram() {
+        let histogram = canonical_histogram(combinations(&CANONICAL_DECK, 2));
+        assert_eq!(histogram.len(), CANONICAL_PREFLOP_HANDS as usize);
+        assert_eq!(histogram.values().sum::<u64>(), 1326);
+        for (hand, count) in &histogram {
+            assert_eq!(*count, isomorphism_class_size(hand) as u64);
+        }
+        assert!(canonical_histogram(Vec::new()).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonical_key(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let key = CanonicalKey::new(hand.clone());
+            assert_eq!(key.cards(), canonicalize_hand(hand.clone()).as_slice());
+
+            let mut map = std::collections::HashMap::new();
+            map.insert(key, 1);
+            let permuted = CanonicalKey::new(permute_suits(hand, permutation));
+            assert_eq!(map.get(&permuted), Some(&1));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_iso_hash_invariant(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let mut permuted = permute_suits(hand.clone(), permutation);
+            permuted[2..].reverse();
+            assert_eq!(iso_hash(&permuted), iso_hash(&hand));
+            assert_eq!(iso_hash(&canonicalize_hand(hand.clone())), iso_hash(&hand));
+        }
+    }
+
+    #[test]
+    fn test_iso_hash_separates_classes() {
+        for (hole_size, board_size, classes) in [(2, 0, 169), (2, 2, 93769)] {
+            let hashes: std::collections::HashSet<_> = enumerate_canonical(hole_size, board_size)
+                .map(|(hand, _)| iso_hash(&hand))
+                .collect();
+            assert_eq!(hashes.len(), classes);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonical_cache(
+            hands in prop::collection::vec(any_hand(), 1..20),
+            capacity in 1usize..8,
+        ) {
+            // every hand is looked up twice, so small caches both hit and evict
+            let mut cache = CanonicalCache::new(capacity);
+            for hand in hands.iter().chain(&hands).chain(hands.iter().rev()) {
+                assert_eq!(cache.canonicalize(hand.clone()), canonicalize_hand(hand.clone()));
+            }
+            let cacheable = hands.iter().filter(|hand| hand.len() <= MAX_CACHED_CARDS).count();
+            assert_eq!(cache.hits() + cache.misses(), 3 * cacheable as u64);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonicalized_iterator(hands in prop::collection::vec(any_hand(), 0..10)) {
+            let expected: Vec<_> = hands.iter().cloned().map(canonicalize_hand).collect();
+            let canonical: Vec<_> = hands.iter().cloned().canonicalized().collect();
+            assert_eq!(canonical, expected);
+
+            let structured: Vec<Hand> = hands
+                .into_iter()
+                .map(|hand| std::convert::TryFrom::try_from(hand).unwrap())
+                .canonicalized()
+                .collect();
+            let structured: Vec<Vec<Card>> = structured.into_iter().map(Vec::from).collect();
+            assert_eq!(structured, expected);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_card_set_canonicalize(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let set: CardSet = hand.iter().copied().collect();
+            let canonical = set.canonicalize();
+            assert_eq!(canonical.to_vec(), canonicalize_cards(&set.to_vec()));
+            assert_eq!(permutation.apply_to_set(set).canonicalize(), canonical);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_enumerate_canonical() {
+        use rayon::iter::ParallelIterator;
+        for (hole_size, board_size) in [(2, 0), (0, 3), (2, 2), (1, 1)] {
+            let parallel: Vec<_> = par_enumerate_canonical(hole_size, board_size).collect();
+            let sequential: Vec<_> = enumerate_canonical(hole_size, board_size).collect();
+            assert_eq!(parallel, sequential);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_canonical_index_invariant(
+            hand in any_hand(),
+            permutation in any_suit_permutation(),
+        ) {
+            let index = canonical_index(&hand);
+            let street = Street::from_board_len(hand.len() - 2).unwrap();
+            assert!(index < street.canonical_count());
+            assert_eq!(canonical_index(&canonicalize_hand(hand.clone())), index);
+
+            let mut permuted = permute_suits(hand, permutation);
+            permuted[..2].reverse();
+            permuted[2..].reverse();
+            assert_eq!(canonical_index(&permuted), index);
+
+            let unindexed = hand_from_index(street, index);
+            assert_eq!(unindexed.to_vec(), canonicalize_hand(permuted));
+        }
+    }
+
+    #[test]
+    fn test_canonical_index_dense() {
+        for street in [Street::Preflop, Street::Flop] {
+            let mut seen = vec![false; street.canonical_count() as usize];
+            for (hand, _) in enumerate_canonical(2, street.board_len()) {
+                let index = canonical_index(&hand) as usize;
+                assert!(!seen[index], "{:?} shares index {}", hand, index);
+                seen[index] = true;
+                // decoding is slower, so spot check it on the later streets
+                if street == Street::Preflop || index.is_multiple_of(97) {
+                    assert_eq!(hand_from_index(street, index as u64).to_vec(), hand);
+                }
+            }
+            assert!(seen.iter().all(|seen| *seen));
+        }
+    }
+
+    #[test]
+    fn test_canonical_boards() {
+        let flops: Vec<_> = canonical_flops().collect();
+        assert_eq!(flops.len(), CANONICAL_FLOPS);
+        assert!(flops.is_sorted());
+        assert!(flops.iter().all(|flop| canonicalize_cards(flop) == *flop));
+        assert_eq!(
+            flops[0],
+            vec![Two.of(Clubs), Two.of(Diamonds), Two.of(Hearts)]
+        );
+
+        assert_eq!(canonical_turns().count(), CANONICAL_TURNS);
+        assert!(canonical_rivers()
+            .take(10)
+            .all(|river| canonicalize_cards(&river) == river));
+    }
+
+    fn count_classes(hole_size: usize, board_size: usize) -> (u64, u64) {
+        enumerate_canonical(hole_size, board_size).fold((0, 0), |(classes, hands), (_, weight)| {
+            (classes + 1, hands + weight as u64)
+        })
+    }
+
+    #[test]
+    fn test_canonical_count_constants() {
+        assert_eq!(count_classes(2, 0), (CANONICAL_PREFLOP_HANDS, 1326));
+        assert_eq!(count_classes(2, 3), (CANONICAL_FLOP_HANDS, 1326 * 19600));
+        assert_eq!(count_classes(0, 3).0, CANONICAL_FLOPS as u64);
+        assert_eq!(count_classes(0, 4).0, CANONICAL_TURNS as u64);
+    }
+
+    // Classes of hands with `hole_size` hole cards and `board_size` board cards by
+    // Burnside's lemma: the average over suit permutations of the hands each fixes.
+    // A hand is fixed when it is a union of the permutation's card cycles, one
+    // cycle per value and suit cycle.
+    fn burnside_classes(hole_size: usize, board_size: usize) -> u64 {
+        let fixed: u64 = SuitPermutation::all()
+            .map(|permutation| {
+                // ways[h][b]: hole and board choices of h and b cards from the cycles so far
+                let mut ways = vec![vec![0u64; board_size + 1]; hole_size + 1];
+                ways[0][0] = 1;
+                for suit in Suit::iter() {
+                    let mut len = 1;
+                    let mut next = permutation.get(suit);
+                    while next > suit {
+                        next = permutation.get(next);
+                        len += 1;
+                    }
+                    // count each suit cycle once, from its lowest suit
+                    if next != suit {
+                        continue;
+                    }
+                    for _ in 0..13 {
+                        for h in (0..=hole_size).rev() {
+                            for b in (0..=board_size).rev() {
+                                if h >= len {
+                                    ways[h][b] += ways[h - len][b];
+                                }
+                                if b >= len {
+                                    ways[h][b] += ways[h][b - len];
+                                }
+                            }
+                        }
+                    }
+                }
+                ways[hole_size][board_size]
+            })
+            .sum();
+        fixed / 24
+    }
+
+    #[test]
+    fn test_canonical_count_constants_burnside() {
+        assert_eq!(burnside_classes(2, 0), CANONICAL_PREFLOP_HANDS);
+        assert_eq!(burnside_classes(2, 3), CANONICAL_FLOP_HANDS);
+        assert_eq!(burnside_classes(2, 4), CANONICAL_TURN_HANDS);
+        assert_eq!(burnside_classes(2, 5), CANONICAL_RIVER_HANDS);
+        assert_eq!(burnside_classes(0, 3), CANONICAL_FLOPS as u64);
+        assert_eq!(burnside_classes(0, 4), CANONICAL_TURNS as u64);
+        assert_eq!(burnside_classes(0, 5), CANONICAL_RIVERS as u64);
+    }
+
+    // these enumerate hundreds of millions of hands, so run them with --ignored in
+    // release; test_canonical_count_constants_burnside checks the same counts cheaply
+    #[test]
+    #[ignore]
+    fn test_canonical_count_constants_exhaustive() {
+        assert_eq!(count_classes(2, 4), (CANONICAL_TURN_HANDS, 1326 * 230300));
+        assert_eq!(count_classes(2, 5), (CANONICAL_RIVER_HANDS, 1326 * 2118760));
+        assert_eq!(count_classes(0, 5).0, CANONICAL_RIVERS as u64);
+    }
+
+    #[test]
+    fn test_isomorphic_variants_counts() {
+        // a suited hand can be any of four suits, a pocket pair any of six pairs of suits
+        assert_eq!(
+            isomorphic_variants(&[Ace.of(Spades), King.of(Spades)]).count(),
+            4
+        );
+        assert_eq!(
+            isomorphic_variants(&[Ace.of(Spades), Ace.of(Hearts)]).count(),
+            6
+        );
+        assert_eq!(
+            isomorphic_variants(&[Ace.of(Spades), King.of(Hearts)]).count(),
+            12
+        );
+        let rainbow = [
+            Ace.of(Spades),
+            King.of(Hearts),
+            Two.of(Clubs),
+            Three.of(Diamonds),
+        ];
+        assert_eq!(isomorphic_variants(&rainbow).count(), 24);
+    }
+
+    #[test]
+    fn test_parse_card() {
+        assert_eq!("As".parse(), Ok(Ace.of(Spades)));
+        assert_eq!(Card::parse_strict("Tc"), Ok(Ten.of(Clubs)));
+        assert_eq!(
+            Card::parse_strict("as"),
+            Err(ParseCardError::InvalidValue('a'))
+        );
+        assert_eq!(
+            Card::parse_strict("AS"),
+            Err(ParseCardError::InvalidSuit('S'))
+        );
+        assert_eq!(
+            Card::parse_strict("10h"),
+            Err(ParseCardError::InvalidLength)
+        );
+        assert_eq!(
+            Card::parse_strict(" As"),
+            Err(ParseCardError::InvalidLength)
+        );
+
+        for s in &["10h", " 10 ♥ ", "th", "TH", "T♡"] {
+            assert_eq!(Card::parse_lenient(s), Ok(Ten.of(Hearts)), "{:?}", s);
+        }
+        assert_eq!(
+            Card::parse_lenient("1h"),
+            Err(ParseCardError::InvalidValue('1'))
+        );
+        assert_eq!(
+            Card::parse_lenient("Ax"),
+            Err(ParseCardError::InvalidSuit('x'))
+        );
+        assert_eq!(
+            Card::parse_lenient("Ahh"),
+            Err(ParseCardError::InvalidLength)
+        );
+        assert_eq!(Card::parse_lenient(""), Err(ParseCardError::InvalidLength));
+
+        for card in CANONICAL_DECK.iter() {
+            let s = format!(
+                "{}{}",
+                "23456789TJQKA".as_bytes()[card.value() as usize - 2] as char,
+                "cdhs".as_bytes()[card.suit() as usize] as char
+            );
+            assert_eq!(card.to_string(), s);
+            assert_eq!(s.parse(), Ok(*card));
+            assert_eq!(Card::parse_lenient(&s.to_uppercase()), Ok(*card));
+            assert_eq!(s[..1].parse(), Ok(card.value()));
+            assert_eq!(s[1..].parse(), Ok(card.suit()));
+        }
+        assert_eq!("t".parse::<Value>(), Err(ParseCardError::InvalidValue('t')));
+        assert_eq!("S".parse::<Suit>(), Err(ParseCardError::InvalidSuit('S')));
+        assert_eq!("10".parse::<Value>(), Err(ParseCardError::InvalidLength));
+        assert_eq!("".parse::<Suit>(), Err(ParseCardError::InvalidLength));
+    }
+
+    #[test]
+    fn test_canonicalize_holdem_distinguishes_turn() {
+        let hole = [Ace.of(Spades), Ace.of(Hearts)];
+        let a = canonicalize_holdem(
+            hole,
+            [Two.of(Spades), Three.of(Spades), Four.of(Diamonds)],
+            Some(Five.of(Clubs)),
+            None,
+        );
+        let b = canonicalize_holdem(
+            hole,
+            [Two.of(Spades), Three.of(Spades), Five.of(Clubs)],
+            Some(Four.of(Diamonds)),
+            None,
+        );
+        assert_ne!(a, b);
+        assert_eq!(a.turn, Some(Five.of(Spades)));
+    }
+
+    #[test]
+    fn test_canonicalize_omaha_hand_double_suited() {
+        let hand = vec![
+            Ace.of(Spades),
+            Ace.of(Hearts),
+            King.of(Spades),
+            King.of(Hearts),
+            Two.of(Hearts),
+            Seven.of(Diamonds),
+            Nine.of(Hearts),
+        ];
+        assert_eq!(
+            canonicalize_omaha_hand(hand),
+            vec![
+                King.of(Clubs),
+                King.of(Diamonds),
+                Ace.of(Clubs),
+                Ace.of(Diamonds),
+                Two.of(Clubs),
+                Seven.of(Hearts),
+                Nine.of(Clubs),
+            ]
+        );
+    }
+
     #[test]
     fn test_canonicalize_hand_perverse_case_one() {
         // Ensure that the hole ambiguity can be resolved by the first card of the table.
@@ -0,0 +1,13 @@
+//! Commonly used types and functions, for glob import.
+//!
+//! ```
+//! use canonical_hand::prelude::*;
+//!
+//! let hand = canonicalize_hand(vec![Value::Ace.of(Suit::Spades), Value::Ace.of(Suit::Hearts)]);
+//! assert_eq!(hand[0], Value::Ace.of(Suit::Clubs));
+//! ```
+
+pub use crate::{
+    canonicalize_hand, Canonicalize, CanonicalizeExt, Card, CardSet, Hand, Suit, SuitPermutation,
+    Value,
+};
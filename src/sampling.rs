@@ -0,0 +1,85 @@
+use crate::{
+    canonicalize_hand, hand_from_index, Card, Hand, Street, SuitPermutation, CANONICAL_DECK,
+};
+use rand::Rng;
+use std::convert::TryFrom;
+
+/// Pick a uniformly random hand from the canonical class of `hand`
+///
+/// Applies a random suit relabelling, so every hand `isomorphic_variants`
+/// produces is equally likely. Cards keep their positions, so the hole cards
+/// stay first.
+pub fn sample_isomorphic<R: Rng + ?Sized>(hand: &[Card], rng: &mut R) -> Vec<Card> {
+    // each variant is reached by the same number of the 24 relabellings
+    let permutation = SuitPermutation::from_code(rng.gen_range(0..24)).unwrap();
+    hand.iter().map(|card| permutation.apply(*card)).collect()
+}
+
+/// Pick a canonical hand on `street`, weighted by the number of deals in its class
+///
+/// Deals uniformly random hole cards and board and canonicalizes them, so each
+/// class is as likely as all of its deals together, as unbiased Monte Carlo
+/// estimates over deals need. The classes' weights are those of
+/// `enumerate_canonical`.
+pub fn sample_canonical<R: Rng + ?Sized>(street: Street, rng: &mut R) -> Hand {
+    let cards = rand::seq::index::sample(rng, CANONICAL_DECK.len(), 2 + street.board_len())
+        .into_iter()
+        .map(|index| CANONICAL_DECK[index])
+        .collect();
+    Hand::try_from(canonicalize_hand(cards)).unwrap()
+}
+
+/// Pick a canonical hand on `street`, with every class equally likely
+///
+/// Picks a uniformly random `canonical_index` and decodes it.
+pub fn sample_canonical_uniform<R: Rng + ?Sized>(street: Street, rng: &mut R) -> Hand {
+    hand_from_index(street, rng.gen_range(0..street.canonical_count()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+    use crate::{hands_equivalent, is_canonical, isomorphic_variants};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_sample_isomorphic_uniform() {
+        let hand = [Ace.of(Spades), Ace.of(Hearts), Two.of(Clubs)];
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = HashMap::new();
+        for _ in 0..12000 {
+            let mut sampled = sample_isomorphic(&hand, &mut rng);
+            assert!(hands_equivalent(&sampled, &hand));
+            sampled[..2].sort();
+            *counts.entry(sampled).or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.len(), isomorphic_variants(&hand).count());
+        assert!(counts.values().all(|count| (800..1200).contains(count)));
+    }
+
+    #[test]
+    fn test_sample_canonical_weights() {
+        // a quarter of deals are suited, but 78 of the 169 classes
+        let mut rng = StdRng::seed_from_u64(0);
+        let suited = |hand: &Hand| hand.hole()[0].suit() == hand.hole()[1].suit();
+        let mut weighted = 0;
+        let mut uniform = 0;
+        for _ in 0..10000 {
+            let hand = sample_canonical(Street::Preflop, &mut rng);
+            assert!(is_canonical(&hand.to_vec()));
+            weighted += suited(&hand) as u32;
+            uniform += suited(&sample_canonical_uniform(Street::Preflop, &mut rng)) as u32;
+        }
+        assert!((2150..2550).contains(&weighted), "{}", weighted);
+        assert!((4400..4850).contains(&uniform), "{}", uniform);
+
+        let river = sample_canonical(Street::River, &mut rng);
+        assert_eq!(river.board().len(), 5);
+        assert!(is_canonical(&river.to_vec()));
+    }
+}
@@ -0,0 +1,208 @@
+//! Short text codes for sharing exact deals.
+//!
+//! A code packs a format version, the number of cards, each card's stable
+//! `Card::to_packed` byte and a CRC-8 checksum into bytes, which are then written in
+//! Crockford base32. Codes only use digits and upper case letters, so they
+//! survive URLs and chat, and are decoded case-insensitively.
+//!
+//! Cards keep their order, so the usual convention of the first two cards being
+//! the hole cards carries through a round trip.
+//!
+//! ```
+//! use canonical_hand::{short_code, Suit::*, Value::*};
+//!
+//! let deal = vec![Ace.of(Spades), King.of(Spades), Two.of(Hearts), Seven.of(Clubs), Ten.of(Spades)];
+//! let code = short_code::encode(&deal);
+//! assert_eq!(short_code::decode(&code), Ok(deal));
+//! ```
+
+use crate::Card;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Version written by `encode`.
+pub const VERSION: u8 = 1;
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Reasons a short code cannot be decoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShortCodeError {
+    /// The code contains a character outside the base32 alphabet.
+    InvalidCharacter(char),
+    /// The code was written by an unknown format version.
+    UnsupportedVersion(u8),
+    /// The code is too short, or its length disagrees with its card count.
+    InvalidLength,
+    /// The checksum does not match, so the code was probably mistyped.
+    ChecksumMismatch,
+    /// A card byte does not name a card.
+    InvalidCard(u8),
+}
+
+impl fmt::Display for ShortCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShortCodeError::InvalidCharacter(c) => write!(f, "invalid character {:?} in code", c),
+            ShortCodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported code version {}", version)
+            }
+            ShortCodeError::InvalidLength => write!(f, "code has the wrong length"),
+            ShortCodeError::ChecksumMismatch => write!(f, "code checksum does not match"),
+            ShortCodeError::InvalidCard(byte) => write!(f, "invalid card {} in code", byte),
+        }
+    }
+}
+
+impl std::error::Error for ShortCodeError {}
+
+/// Encode a deal as a short code.
+///
+/// Panics if there are more than 255 cards.
+pub fn encode(cards: &[Card]) -> String {
+    let mut bytes = Vec::with_capacity(cards.len() + 3);
+    bytes.push(VERSION);
+    bytes.push(u8::try_from(cards.len()).expect("too many cards for a short code"));
+    bytes.extend(cards.iter().map(|card| card.to_packed()));
+    bytes.push(crc8(&bytes));
+    to_base32(&bytes)
+}
+
+/// Decode a short code produced by `encode`.
+pub fn decode(code: &str) -> Result<Vec<Card>, ShortCodeError> {
+    let bytes = from_base32(code)?;
+    let (checksum, payload) = bytes.split_last().ok_or(ShortCodeError::InvalidLength)?;
+    let (version, count, cards) = match payload {
+        [version, count, cards @ ..] => (*version, *count, cards),
+        _ => return Err(ShortCodeError::InvalidLength),
+    };
+
+    if crc8(payload) != *checksum {
+        return Err(ShortCodeError::ChecksumMismatch);
+    }
+    if version != VERSION {
+        return Err(ShortCodeError::UnsupportedVersion(version));
+    }
+    if cards.len() != count as usize {
+        return Err(ShortCodeError::InvalidLength);
+    }
+
+    cards
+        .iter()
+        .map(|byte| Card::from_packed(*byte).ok_or(ShortCodeError::InvalidCard(*byte)))
+        .collect()
+}
+
+// CRC-8 with polynomial x^8 + x^2 + x + 1
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+fn to_base32(bytes: &[u8]) -> String {
+    let mut code = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = buffer << 8 | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            code.push(ALPHABET[(buffer >> bits & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        code.push(ALPHABET[(buffer << (5 - bits) & 31) as usize] as char);
+    }
+    code
+}
+
+fn from_base32(code: &str) -> Result<Vec<u8>, ShortCodeError> {
+    let mut bytes = Vec::with_capacity(code.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in code.chars() {
+        let digit = ALPHABET
+            .iter()
+            .position(|a| *a as char == c.to_ascii_uppercase())
+            .ok_or(ShortCodeError::InvalidCharacter(c))?;
+        buffer = buffer << 5 | digit as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    // leftover bits are padding from encoding, so there are fewer than a character's
+    // worth and they must be zero; anything else would give a deal several codes
+    if bits >= 5 {
+        return Err(ShortCodeError::InvalidLength);
+    }
+    if buffer & ((1 << bits) - 1) != 0 {
+        let last = code.chars().last().unwrap();
+        return Err(ShortCodeError::InvalidCharacter(last));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+    use crate::CANONICAL_DECK;
+
+    #[test]
+    fn test_short_code_pinned() {
+        // published codes must keep decoding to the same deal
+        let deal = vec![
+            Ace.of(Spades),
+            King.of(Hearts),
+            Two.of(Clubs),
+            Ten.of(Diamonds),
+            Seven.of(Spades),
+        ];
+        let code = encode(&deal);
+        assert_eq!(code, "042K6BG044BQC");
+        assert_eq!(decode(&code), Ok(deal));
+    }
+
+    #[test]
+    fn test_short_code_round_trip() {
+        for len in 0..=CANONICAL_DECK.len() {
+            let cards: Vec<_> = CANONICAL_DECK.iter().rev().take(len).copied().collect();
+            let code = encode(&cards);
+            assert_eq!(decode(&code), Ok(cards.clone()));
+            assert_eq!(decode(&code.to_ascii_lowercase()), Ok(cards));
+        }
+    }
+
+    #[test]
+    fn test_short_code_errors() {
+        let code = encode(&[Ace.of(Spades), Ace.of(Hearts), Two.of(Clubs)]);
+        assert_eq!(decode(""), Err(ShortCodeError::InvalidLength));
+        assert_eq!(decode("U"), Err(ShortCodeError::InvalidCharacter('U')));
+
+        // each deal has exactly one code, so extra padding is rejected
+        assert_eq!(
+            decode(&format!("{}0", code)),
+            Err(ShortCodeError::InvalidLength)
+        );
+
+        // changing any character must be caught by the checksum
+        for i in 0..code.len() {
+            let mut corrupted = code.clone().into_bytes();
+            corrupted[i] = if corrupted[i] == b'0' { b'1' } else { b'0' };
+            let corrupted = String::from_utf8(corrupted).unwrap();
+            assert!(decode(&corrupted).is_err(), "{} decoded", corrupted);
+        }
+    }
+}
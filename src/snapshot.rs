@@ -0,0 +1,128 @@
+//! Snapshots of canonical forms, for checking an upgrade of this crate
+//! against data produced by an older version.
+//!
+//! A snapshot is text with one hand per line: the hand's short code, a space,
+//! then the short code of its canonical form. Short codes use the stable
+//! `Card::to_packed` encoding, so a snapshot written by any version can be
+//! read by any later one.
+//!
+//! ```
+//! use canonical_hand::{combinations, snapshot, CANONICAL_DECK};
+//!
+//! let preflop = combinations(&CANONICAL_DECK, 2);
+//! let mut recorded = Vec::new();
+//! snapshot::write(preflop, &mut recorded).unwrap();
+//!
+//! // ... after upgrading, diff the stored snapshot against the new version
+//! assert!(snapshot::diff(&recorded[..]).unwrap().is_empty());
+//! ```
+
+use crate::{canonicalize_hand, short_code, Card};
+use std::io::{self, BufRead, Write};
+
+/// A hand whose canonical form differs from the one recorded in a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub hand: Vec<Card>,
+    /// Canonical form recorded in the snapshot.
+    pub recorded: Vec<Card>,
+    /// Canonical form produced by this version.
+    pub current: Vec<Card>,
+}
+
+/// Write a snapshot of the canonical form of every hand.
+///
+/// Panics if a hand has fewer than two cards.
+pub fn write<I: IntoIterator<Item = Vec<Card>>, W: Write>(
+    hands: I,
+    mut writer: W,
+) -> io::Result<()> {
+    for hand in hands {
+        let canonical = canonicalize_hand(hand.clone());
+        writeln!(
+            writer,
+            "{} {}",
+            short_code::encode(&hand),
+            short_code::encode(&canonical)
+        )?;
+    }
+    Ok(())
+}
+
+/// Canonicalize every hand in a snapshot, reporting those whose canonical form has changed.
+///
+/// Fails with `io::ErrorKind::InvalidData` if a line is not a pair of valid
+/// short codes or its hand has fewer than two cards.
+pub fn diff<R: BufRead>(reader: R) -> io::Result<Vec<Change>> {
+    let mut changes = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let invalid = |reason: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}", number + 1, reason),
+            )
+        };
+
+        let mut codes = line.split(' ');
+        let (hand, recorded) = match (codes.next(), codes.next(), codes.next()) {
+            (Some(hand), Some(recorded), None) => (hand, recorded),
+            _ => return Err(invalid("expected two short codes".to_string())),
+        };
+        let hand = short_code::decode(hand).map_err(|error| invalid(error.to_string()))?;
+        let recorded = short_code::decode(recorded).map_err(|error| invalid(error.to_string()))?;
+        if hand.len() < 2 {
+            return Err(invalid("hand has fewer than two cards".to_string()));
+        }
+
+        let current = canonicalize_hand(hand.clone());
+        if current != recorded {
+            changes.push(Change {
+                hand,
+                recorded,
+                current,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_diff_reports_changes() {
+        let hand = vec![Ace.of(Spades), King.of(Spades), Two.of(Hearts)];
+        let stale = vec![Ace.of(Clubs), King.of(Clubs), Two.of(Hearts)];
+        let snapshot = format!(
+            "{} {}\n",
+            short_code::encode(&hand),
+            short_code::encode(&stale)
+        );
+        assert_eq!(
+            diff(snapshot.as_bytes()).unwrap(),
+            vec![Change {
+                current: canonicalize_hand(hand.clone()),
+                hand,
+                recorded: stale,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_rejects_malformed_lines() {
+        let code = short_code::encode(&[Ace.of(Spades), King.of(Spades)]);
+        for snapshot in &[
+            code.clone(),
+            format!("{} {} {}", code, code, code),
+            format!("{} !", code),
+        ] {
+            let error = diff(snapshot.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().starts_with("line 1:"));
+        }
+    }
+}
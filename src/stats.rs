@@ -0,0 +1,111 @@
+//! Summary statistics over a corpus of hands.
+
+use crate::{try_canonicalize_hand, Card};
+use std::collections::{BTreeMap, HashSet};
+
+/// Report produced by `summarize`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of hands seen, including invalid ones.
+    pub hands: usize,
+    /// Hands which could not be canonicalized (too few or repeated cards).
+    pub invalid_hands: usize,
+    /// Number of valid hands of each size, e.g. 5 cards for the flop.
+    pub hands_by_size: BTreeMap<usize, usize>,
+    /// Number of distinct valid hands, exactly as given.
+    pub distinct_hands: usize,
+    /// Number of distinct canonical classes covered, by hand size.
+    pub canonical_classes_by_size: BTreeMap<usize, usize>,
+}
+
+impl Summary {
+    /// Number of distinct canonical classes covered.
+    pub fn canonical_classes(&self) -> usize {
+        self.canonical_classes_by_size.values().sum()
+    }
+
+    /// Fraction of valid hands which repeat an earlier hand exactly.
+    pub fn duplicate_rate(&self) -> f64 {
+        let valid = self.hands - self.invalid_hands;
+        if valid == 0 {
+            0.0
+        } else {
+            1.0 - self.distinct_hands as f64 / valid as f64
+        }
+    }
+
+    /// Fraction of valid hands which are strategically equivalent to an earlier hand.
+    pub fn canonical_duplicate_rate(&self) -> f64 {
+        let valid = self.hands - self.invalid_hands;
+        if valid == 0 {
+            0.0
+        } else {
+            1.0 - self.canonical_classes() as f64 / valid as f64
+        }
+    }
+}
+
+/// Summarize a corpus of hands, each with the hole cards first.
+pub fn summarize<I: IntoIterator<Item = Vec<Card>>>(hands: I) -> Summary {
+    let mut summary = Summary::default();
+    let mut distinct = HashSet::new();
+    let mut classes = HashSet::new();
+
+    for hand in hands {
+        summary.hands += 1;
+        let canonical = match try_canonicalize_hand(hand.clone()) {
+            Ok(canonical) => canonical,
+            Err(_) => {
+                summary.invalid_hands += 1;
+                continue;
+            }
+        };
+
+        *summary.hands_by_size.entry(hand.len()).or_insert(0) += 1;
+        distinct.insert(hand);
+        if classes.insert(canonical.clone()) {
+            *summary
+                .canonical_classes_by_size
+                .entry(canonical.len())
+                .or_insert(0) += 1;
+        }
+    }
+
+    summary.distinct_hands = distinct.len();
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_summarize() {
+        let summary = summarize(vec![
+            vec![Ace.of(Spades), Ace.of(Hearts)],
+            vec![Ace.of(Spades), Ace.of(Hearts)],
+            vec![Ace.of(Clubs), Ace.of(Diamonds)],
+            vec![
+                Ace.of(Clubs),
+                Ace.of(Diamonds),
+                Two.of(Clubs),
+                Three.of(Clubs),
+                Four.of(Clubs),
+            ],
+            vec![Ace.of(Clubs)],
+        ]);
+
+        assert_eq!(summary.hands, 5);
+        assert_eq!(summary.invalid_hands, 1);
+        assert_eq!(
+            summary.hands_by_size,
+            vec![(2, 3), (5, 1)].into_iter().collect()
+        );
+        assert_eq!(summary.distinct_hands, 3);
+        assert_eq!(summary.canonical_classes(), 2);
+        assert_eq!(summary.duplicate_rate(), 0.25);
+        assert_eq!(summary.canonical_duplicate_rate(), 0.5);
+    }
+}
@@ -2,7 +2,7 @@ use crate::Suit;
 use num_traits::FromPrimitive;
 
 // Map from suit to some value
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) struct SuitMap<T>([T; 4]);
 
 impl<T: Copy> SuitMap<T> {
@@ -38,3 +38,22 @@ impl<T> From<[T; 4]> for SuitMap<T> {
         Self(other)
     }
 }
+
+/// Iterate all 24 suit permutations, starting with the identity.
+///
+/// Permutations are produced in lexicographic order of their targets.
+pub(crate) fn all_suit_permutations() -> impl Iterator<Item = SuitMap<Suit>> {
+    (0..24).map(|mut index| {
+        let mut remaining = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+        let mut targets = remaining;
+        for (position, target) in targets.iter_mut().enumerate() {
+            // index is a factorial-base number, one digit per position
+            let radix = (1..4 - position).product::<usize>();
+            let chosen = index / radix;
+            index %= radix;
+            *target = remaining[chosen];
+            remaining[chosen..].rotate_left(1);
+        }
+        targets.into()
+    })
+}
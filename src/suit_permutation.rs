@@ -0,0 +1,188 @@
+use crate::suit_map::{all_suit_permutations, SuitMap};
+use crate::{Card, CardSet, Suit};
+use strum::IntoEnumIterator;
+
+/// Relabelling of the four suits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SuitPermutation(SuitMap<Suit>);
+
+impl SuitPermutation {
+    /// Create a permutation from the target of each suit.
+    ///
+    /// `targets` is in the order `[Clubs, Diamonds, Hearts, Spades]`, so
+    /// `[Hearts, Diamonds, Spades, Clubs]` relabels Clubs as Hearts, leaves
+    /// Diamonds alone, and so on. Returns None unless every suit appears
+    /// exactly once.
+    pub fn new(targets: [Suit; 4]) -> Option<Self> {
+        let mut seen = [false; 4];
+        for target in &targets {
+            seen[*target as usize] = true;
+        }
+        seen.iter().all(|seen| *seen).then(|| Self(targets.into()))
+    }
+
+    /// The permutation which leaves every suit unchanged.
+    pub fn identity() -> Self {
+        Self(SuitMap::from([
+            Suit::Clubs,
+            Suit::Diamonds,
+            Suit::Hearts,
+            Suit::Spades,
+        ]))
+    }
+
+    /// All 24 permutations, starting with the identity.
+    pub fn all() -> impl Iterator<Item = SuitPermutation> {
+        all_suit_permutations().map(Self)
+    }
+
+    /// The permutation applying `first`, then `self`.
+    pub fn compose(&self, first: &SuitPermutation) -> Self {
+        Self(first.0.map(|suit| self.get(suit)))
+    }
+
+    /// The permutation undoing `self`.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = *self;
+        for (suit, target) in self.0.iter() {
+            *inverse.0.get_mut(*target) = suit;
+        }
+        inverse
+    }
+
+    /// Compact code for this permutation, from 0 to 23.
+    ///
+    /// Codes follow the order of `SuitPermutation::all`, so the identity is 0.
+    /// This is the serialized form of a permutation.
+    pub fn code(&self) -> u8 {
+        let targets =
+            [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades].map(|suit| self.get(suit));
+        targets
+            .iter()
+            .enumerate()
+            .fold(0, |code, (position, target)| {
+                // factorial-base digit: how many later suits map lower than this one
+                let digit = targets[position + 1..]
+                    .iter()
+                    .filter(|later| *later < target)
+                    .count();
+                code * (4 - position) as u8 + digit as u8
+            })
+    }
+
+    /// The permutation with the given `code`, or None if it is 24 or more.
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::all().nth(code as usize)
+    }
+
+    /// The suit that `suit` is relabelled as.
+    pub fn get(&self, suit: Suit) -> Suit {
+        *self.0.get(suit)
+    }
+
+    /// Relabel the suit of a single card.
+    pub fn apply(&self, card: Card) -> Card {
+        card.value().of(self.get(card.suit()))
+    }
+
+    /// Relabel the suits of every card in a set.
+    ///
+    /// Each suit is a 16-bit lane of the set, so this moves four lanes rather
+    /// than visiting each card.
+    pub fn apply_to_set(&self, cards: CardSet) -> CardSet {
+        let bits = Suit::iter().fold(0, |bits, suit| {
+            bits | (cards.suit_mask(suit) as u64) << (self.get(suit) as u64 * 16)
+        });
+        CardSet::from_bits(bits)
+    }
+}
+
+impl From<SuitMap<Suit>> for SuitPermutation {
+    fn from(map: SuitMap<Suit>) -> Self {
+        debug_assert!(
+            Suit::iter().all(|suit| map.iter().any(|(_, target)| *target == suit)),
+            "suit map must contain all four suits"
+        );
+        Self(map)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SuitPermutation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SuitPermutation {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = u8::deserialize(deserializer)?;
+        Self::from_code(code).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid suit permutation code {}", code))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_suit_permutation_new() {
+        assert!(SuitPermutation::new([Clubs, Clubs, Hearts, Spades]).is_none());
+        let permutation = SuitPermutation::new([Hearts, Diamonds, Spades, Clubs]).unwrap();
+        assert_eq!(permutation.get(Clubs), Hearts);
+        assert_eq!(permutation.get(Spades), Clubs);
+        assert_eq!(permutation.apply(Ace.of(Hearts)), Ace.of(Spades));
+        assert_eq!(
+            SuitPermutation::new([Clubs, Diamonds, Hearts, Spades]),
+            Some(SuitPermutation::identity())
+        );
+    }
+
+    #[test]
+    fn test_suit_permutation_code() {
+        for (code, permutation) in SuitPermutation::all().enumerate() {
+            assert_eq!(permutation.code() as usize, code);
+            assert_eq!(SuitPermutation::from_code(code as u8), Some(permutation));
+        }
+        assert_eq!(SuitPermutation::identity().code(), 0);
+        assert_eq!(SuitPermutation::from_code(24), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_suit_permutation_deserialize() {
+        use serde::de::{value::Error, IntoDeserializer};
+        use serde::Deserialize;
+
+        let permutation = SuitPermutation::deserialize(5u8.into_deserializer());
+        assert_eq!(
+            permutation,
+            Ok::<_, Error>(SuitPermutation::from_code(5).unwrap())
+        );
+        assert!(
+            SuitPermutation::deserialize(IntoDeserializer::<Error>::into_deserializer(24u8))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_suit_permutation_group_operations() {
+        let identity = SuitPermutation::identity();
+        for a in SuitPermutation::all() {
+            assert_eq!(a.compose(&a.inverse()), identity);
+            assert_eq!(a.inverse().compose(&a), identity);
+            assert_eq!(a.compose(&identity), a);
+            for b in SuitPermutation::all() {
+                let composed = a.compose(&b);
+                for suit in Suit::iter() {
+                    assert_eq!(composed.get(suit), a.get(b.get(suit)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Helpers for downstream code to check that it respects suit symmetry.
+
+use crate::suit_map::all_suit_permutations;
+use crate::{canonicalize_hand, canonicalize_holdem, Canonicalizer, Card};
+use std::fmt::Debug;
+
+/// Assert that `f` returns the same value for `hand` under every suit permutation.
+///
+/// All 24 relabelings of the suits are tried exhaustively. Cards keep their
+/// positions in the hand, so hole/board conventions are preserved; only the
+/// suits change.
+///
+/// This is intended for checking hand features before they are used with
+/// canonicalized hands: a feature which fails this check can give different
+/// answers for strategically equivalent hands.
+///
+/// Panics with the offending permutation if `f` is not invariant.
+pub fn assert_suit_invariant<T, F>(hand: &[Card], f: F)
+where
+    T: PartialEq + Debug,
+    F: Fn(&[Card]) -> T,
+{
+    let expected = f(hand);
+    let mut permuted = hand.to_vec();
+
+    for permutation in all_suit_permutations() {
+        for (card, original) in permuted.iter_mut().zip(hand) {
+            *card = original.with_suit(*permutation.get(original.suit()));
+        }
+
+        let actual = f(&permuted);
+        assert!(
+            actual == expected,
+            "function is not suit invariant: {:?} gave {:?}, but permuted hand {:?} gave {:?}",
+            hand,
+            expected,
+            permuted,
+            actual
+        );
+    }
+}
+
+/// Assert that a `Canonicalizer` fed street by street agrees with batch canonicalization.
+///
+/// `board` is the flop followed by the turn and river if dealt. After each
+/// street, the canonicalizer's cards must equal `canonicalize_holdem` of the
+/// cards dealt so far (`canonicalize_hand` of the hole cards alone), and the
+/// canonical cards of earlier streets must be unchanged.
+///
+/// Incremental pipelines can run the same comparison against their own
+/// street-by-street state to check it matches batch canonicalization.
+///
+/// Panics with the street where the two disagree, or if `board` is not three
+/// to five cards.
+pub fn assert_incremental_matches_batch(hole: [Card; 2], board: &[Card]) {
+    assert!(
+        (3..=5).contains(&board.len()),
+        "board must be a flop, turn or river"
+    );
+
+    let mut canonicalizer = Canonicalizer::new();
+    canonicalizer.push_hole(hole);
+    let mut expected = canonicalize_hand(hole.to_vec());
+    check_street("hole", &canonicalizer, &expected);
+
+    canonicalizer.push_flop([board[0], board[1], board[2]]);
+    for (dealt, street) in (3..=board.len()).zip(["flop", "turn", "river"]) {
+        if dealt == 4 {
+            canonicalizer.push_turn(board[3]);
+        } else if dealt == 5 {
+            canonicalizer.push_river(board[4]);
+        }
+
+        let batch = canonicalize_holdem(
+            hole,
+            [board[0], board[1], board[2]],
+            board.get(3).filter(|_| dealt >= 4).copied(),
+            board.get(4).filter(|_| dealt >= 5).copied(),
+        );
+        let previous = expected;
+        expected = batch.hole.iter().chain(&batch.flop).copied().collect();
+        expected.extend(batch.turn.iter().chain(batch.river.iter()));
+
+        assert!(
+            expected.starts_with(&previous),
+            "batch canonicalization changed earlier streets at the {}: {:?} became {:?}",
+            street,
+            previous,
+            expected
+        );
+        check_street(street, &canonicalizer, &expected);
+    }
+}
+
+fn check_street(street: &str, canonicalizer: &Canonicalizer, expected: &[Card]) {
+    assert!(
+        canonicalizer.canonical() == expected,
+        "incremental canonicalization differs at the {}: got {:?}, but batch gave {:?}",
+        street,
+        canonicalizer.canonical(),
+        expected
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    fn hand() -> Vec<Card> {
+        vec![
+            Ace.of(Spades),
+            King.of(Spades),
+            Two.of(Clubs),
+            Seven.of(Spades),
+            Jack.of(Hearts),
+        ]
+    }
+
+    #[test]
+    fn test_assert_suit_invariant_accepts_invariant_feature() {
+        assert_suit_invariant(&hand(), |cards| cards[0].suit() == cards[1].suit());
+    }
+
+    #[test]
+    #[should_panic(expected = "function is not suit invariant")]
+    fn test_assert_suit_invariant_rejects_suit_specific_feature() {
+        assert_suit_invariant(&hand(), |cards| {
+            cards.iter().filter(|card| card.suit() == Spades).count()
+        });
+    }
+
+    #[test]
+    fn test_assert_incremental_matches_batch() {
+        let hand = hand();
+        assert_incremental_matches_batch([hand[0], hand[1]], &hand[2..]);
+        assert_incremental_matches_batch(
+            [Two.of(Hearts), Two.of(Diamonds)],
+            &[
+                Two.of(Spades),
+                Three.of(Clubs),
+                Four.of(Hearts),
+                Nine.of(Spades),
+                Nine.of(Clubs),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "board must be a flop, turn or river")]
+    fn test_assert_incremental_matches_batch_rejects_preflop() {
+        assert_incremental_matches_batch([Ace.of(Spades), King.of(Spades)], &[]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Rules shared by trick-taking games such as bridge and whist.
+//!
+//! Cards rank Ace high within each suit, as `Value` orders them.
+
+use crate::{canonicalize_zones, canonicalize_zones_with_pinned_suit, Card, Suit, ZoneSpec};
+
+/// Whether `card` may be played from `hand` to a trick led in `led`.
+///
+/// A player must follow the suit led if they can; otherwise, or when leading
+/// (`led` is None), any card in the hand may be played.
+pub fn is_legal_play(hand: &[Card], card: Card, led: Option<Suit>) -> bool {
+    if !hand.contains(&card) {
+        return false;
+    }
+    match led {
+        Some(led) if card.suit() != led => hand.iter().all(|held| held.suit() != led),
+        _ => true,
+    }
+}
+
+/// The cards of `hand` which may be played to a trick led in `led`, in hand order.
+pub fn legal_plays(hand: &[Card], led: Option<Suit>) -> Vec<Card> {
+    hand.iter()
+        .copied()
+        .filter(|card| is_legal_play(hand, *card, led))
+        .collect()
+}
+
+/// Position within `trick` of the card which wins it.
+///
+/// The first card is the lead. The highest trump wins if any trumps were
+/// played, otherwise the highest card of the suit led. Panics if the trick is
+/// empty.
+pub fn trick_winner(trick: &[Card], trump: Option<Suit>) -> usize {
+    let led = trick.first().expect("trick has no cards").suit();
+    let winning_suit = match trump {
+        Some(trump) if trick.iter().any(|card| card.suit() == trump) => trump,
+        _ => led,
+    };
+
+    let (position, _) = trick
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| card.suit() == winning_suit)
+        .max_by_key(|(_, card)| card.value())
+        .unwrap();
+    position
+}
+
+/// Canonicalize a bridge deal of four 13-card hands under suit relabelling
+///
+/// Seats keep their hands, and each hand is sorted. The result is the
+/// lexicographic minimum comparing the hands in seat order, so equivalent
+/// deals share a key, e.g. in a double-dummy solver cache. With a `trump`
+/// suit it is left alone and only the other three suits are relabelled.
+pub fn canonicalize_deal(hands: [[Card; 13]; 4], trump: Option<Suit>) -> [[Card; 13]; 4] {
+    let mut cards: Vec<Card> = hands.iter().flatten().copied().collect();
+    let zones = [ZoneSpec::unordered(13); 4];
+    match trump {
+        Some(trump) => canonicalize_zones_with_pinned_suit(&mut cards, &zones, trump),
+        None => canonicalize_zones(&mut cards, &zones),
+    };
+
+    let mut canonical = hands;
+    for (hand, cards) in canonical.iter_mut().zip(cards.chunks_exact(13)) {
+        hand.copy_from_slice(cards);
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Suit::*;
+    use crate::Value::*;
+
+    #[test]
+    fn test_follow_suit() {
+        let hand = [Ace.of(Spades), Two.of(Hearts), King.of(Clubs)];
+        assert!(is_legal_play(&hand, Ace.of(Spades), None));
+        assert!(is_legal_play(&hand, Two.of(Hearts), Some(Hearts)));
+        assert!(!is_legal_play(&hand, Ace.of(Spades), Some(Hearts)));
+        assert!(is_legal_play(&hand, Ace.of(Spades), Some(Diamonds)));
+        assert!(!is_legal_play(&hand, Ace.of(Hearts), None));
+        assert_eq!(legal_plays(&hand, Some(Clubs)), vec![King.of(Clubs)]);
+        assert_eq!(legal_plays(&hand, Some(Diamonds)), hand.to_vec());
+    }
+
+    #[test]
+    fn test_canonicalize_deal() {
+        // each player holds one suit
+        let mut hands = [[Two.of(Clubs); 13]; 4];
+        for (hand, suit) in hands.iter_mut().zip(&[Spades, Hearts, Clubs, Diamonds]) {
+            *hand = crate::cards_of_suit(*suit);
+        }
+
+        let canonical = canonicalize_deal(hands, None);
+        for (hand, suit) in canonical.iter().zip(&[Clubs, Diamonds, Hearts, Spades]) {
+            assert_eq!(*hand, crate::cards_of_suit(*suit));
+        }
+
+        let canonical = canonicalize_deal(hands, Some(Hearts));
+        for (hand, suit) in canonical.iter().zip(&[Clubs, Hearts, Diamonds, Spades]) {
+            assert_eq!(*hand, crate::cards_of_suit(*suit));
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_deal_invariant() {
+        // deal the deck in an irregular order, then relabel it every possible way
+        let mut hands = [[Two.of(Clubs); 13]; 4];
+        for (index, card) in crate::CANONICAL_DECK.iter().enumerate() {
+            let position = index * 7 % 52;
+            hands[position / 13][position % 13] = *card;
+        }
+
+        let canonical = canonicalize_deal(hands, None);
+        let with_trump = canonicalize_deal(hands, Some(Spades));
+        for permutation in crate::SuitPermutation::all() {
+            let permuted = hands.map(|hand| hand.map(|card| permutation.apply(card)));
+            assert_eq!(canonicalize_deal(permuted, None), canonical);
+            if permutation.get(Spades) == Spades {
+                assert_eq!(canonicalize_deal(permuted, Some(Spades)), with_trump);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trick_winner() {
+        let trick = [
+            Ten.of(Hearts),
+            Ace.of(Spades),
+            Queen.of(Hearts),
+            Two.of(Clubs),
+        ];
+        assert_eq!(trick_winner(&trick, None), 2);
+        assert_eq!(trick_winner(&trick, Some(Clubs)), 3);
+        assert_eq!(trick_winner(&trick, Some(Spades)), 1);
+        assert_eq!(trick_winner(&trick, Some(Diamonds)), 2);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{canonicalize_hand, Card, Value};
+use num_traits::FromPrimitive;
+use std::cmp::Ordering;
+
+/// Ranking of card values, which varies between games.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ValueOrder {
+    /// Two is lowest and Ace highest, as `Value` orders them.
+    #[default]
+    AceHigh,
+    /// Ace is lowest, then Two up to King, as in Razz and ace-to-five lowball.
+    AceLow,
+}
+
+impl ValueOrder {
+    /// Compare two cards by value in this ordering, then by suit.
+    pub fn cmp_cards(self, a: &Card, b: &Card) -> Ordering {
+        self.to_ace_high(*a).cmp(&self.to_ace_high(*b))
+    }
+
+    // Relabel values so that the usual ordering of the result matches this ordering.
+    fn to_ace_high(self, card: Card) -> Card {
+        match (self, card.value()) {
+            (ValueOrder::AceHigh, _) => card,
+            (ValueOrder::AceLow, Value::Ace) => Value::Two.of(card.suit()),
+            (ValueOrder::AceLow, value) => Value::from_u8(value as u8 + 1).unwrap().of(card.suit()),
+        }
+    }
+
+    fn undo_to_ace_high(self, card: Card) -> Card {
+        match (self, card.value()) {
+            (ValueOrder::AceHigh, _) => card,
+            (ValueOrder::AceLow, Value::Two) => Value::Ace.of(card.suit()),
+            (ValueOrder::AceLow, value) => Value::from_u8(value as u8 - 1).unwrap().of(card.suit()),
+        }
+    }
+}
+
+/// Get strategically equivalent hand with lexicographic minimum under `order`
+///
+/// As `canonicalize_hand`, but cards are compared using `order`, so with
+/// `ValueOrder::AceLow` aces sort first and the canonical hand is the one a
+/// Razz or ace-to-five player would expect. Panics if there are fewer than
+/// two cards.
+pub fn canonicalize_hand_with_order(cards: Vec<Card>, order: ValueOrder) -> Vec<Card> {
+    // relabelling values commutes with relabelling suits, so the canonical form
+    // of the relabelled hand is the relabelled canonical form
+    let relabelled = cards
+        .into_iter()
+        .map(|card| order.to_ace_high(card))
+        .collect();
+    canonicalize_hand(relabelled)
+        .into_iter()
+        .map(|card| order.undo_to_ace_high(card))
+        .collect()
+}
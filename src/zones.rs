@@ -0,0 +1,480 @@
+use crate::{canonicalize_hand, Card, Suit, SuitPermutation};
+
+/// Layout of one zone of cards within a flat hand.
+///
+/// A zone is a group of cards which play the same role, such as the hole
+/// cards or one street of the board. Cards never move between zones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ZoneSpec {
+    /// Number of cards in the zone.
+    pub len: usize,
+    /// Whether the position of cards within the zone matters. Unordered zones
+    /// are sorted in the canonical form.
+    pub ordered: bool,
+}
+
+impl ZoneSpec {
+    /// A zone of `len` cards whose order doesn't matter, like a flop.
+    pub const fn unordered(len: usize) -> Self {
+        Self {
+            len,
+            ordered: false,
+        }
+    }
+
+    /// A zone of `len` cards which keep their positions, like successive streets.
+    pub const fn ordered(len: usize) -> Self {
+        Self { len, ordered: true }
+    }
+}
+
+/// Canonicalize a hand made of consecutive zones, in place.
+///
+/// The canonical form is the lexicographic minimum over suit permutations of
+/// the zones compared one after another. `candidates` is narrowed zone by zone
+/// to the permutations which reach the minimum so far, and on return holds
+/// every permutation reaching the canonical form, in their original order.
+///
+/// This costs a constant factor of up to 24 over sorting the hand, but handles
+/// any number of zones of any size, unlike the specialised Hold'em algorithm.
+pub(crate) fn canonicalize_zones_in_place(
+    cards: &mut [Card],
+    zones: &[ZoneSpec],
+    candidates: &mut Vec<SuitPermutation>,
+) {
+    assert_eq!(
+        zones.iter().map(|zone| zone.len).sum::<usize>(),
+        cards.len(),
+        "zones must cover the hand exactly"
+    );
+    assert!(!candidates.is_empty(), "no candidate permutations");
+
+    let mut start = 0;
+    for zone in zones {
+        let zone_cards = &mut cards[start..start + zone.len];
+        canonicalize_zone_in_place(zone_cards, zone.ordered, candidates);
+        start += zone.len;
+    }
+}
+
+/// Canonicalize the next zone of a hand whose earlier zones were canonicalized
+/// with the same `candidates`, narrowing `candidates` to those which also
+/// minimise this zone.
+pub(crate) fn canonicalize_zone_in_place(
+    zone_cards: &mut [Card],
+    ordered: bool,
+    candidates: &mut Vec<SuitPermutation>,
+) {
+    let mut best = Vec::with_capacity(zone_cards.len());
+    let mut image = Vec::with_capacity(zone_cards.len());
+    let mut kept = 0;
+
+    for index in 0..candidates.len() {
+        let permutation = candidates[index];
+        image.clear();
+        image.extend(zone_cards.iter().map(|card| permutation.apply(*card)));
+        if !ordered {
+            image.sort_unstable();
+        }
+
+        if kept == 0 || image < best {
+            std::mem::swap(&mut best, &mut image);
+            candidates[0] = permutation;
+            kept = 1;
+        } else if image == best {
+            candidates[kept] = permutation;
+            kept += 1;
+        }
+    }
+
+    candidates.truncate(kept);
+    zone_cards.copy_from_slice(&best);
+}
+
+/// Canonicalize a hand made of consecutive zones, in place
+///
+/// The canonical form is the suit relabelling of `cards` which is
+/// lexicographically smallest, comparing zones one after another, with each
+/// unordered zone sorted. This is the engine behind the game-specific
+/// functions in this crate, and can describe other layouts directly:
+///
+/// ```
+/// use canonical_hand::{canonicalize_zones, Suit::*, Value::*, ZoneSpec};
+///
+/// // two hole cards, a flop, then the turn
+/// let mut cards = [
+///     Ace.of(Hearts), King.of(Hearts),
+///     Two.of(Spades), Three.of(Spades), Four.of(Hearts),
+///     Five.of(Diamonds),
+/// ];
+/// let zones = [ZoneSpec::unordered(2), ZoneSpec::unordered(3), ZoneSpec::ordered(1)];
+/// canonicalize_zones(&mut cards, &zones);
+/// assert_eq!(cards, [
+///     King.of(Clubs), Ace.of(Clubs),
+///     Two.of(Diamonds), Three.of(Diamonds), Four.of(Clubs),
+///     Five.of(Hearts),
+/// ]);
+/// ```
+///
+/// Returns the permutation which was applied, choosing the first in
+/// `SuitPermutation::all` order when several reach the canonical form. Panics
+/// if the zones don't cover `cards` exactly.
+pub fn canonicalize_zones(cards: &mut [Card], zones: &[ZoneSpec]) -> SuitPermutation {
+    let mut candidates: Vec<_> = SuitPermutation::all().collect();
+    canonicalize_zones_in_place(cards, zones, &mut candidates);
+    candidates[0]
+}
+
+/// Canonicalize a hand made of consecutive zones, never relabelling the `pinned` suit
+///
+/// As `canonicalize_zones`, but only the other three suits are exchangeable,
+/// as when one suit is trumps or carries its own scoring. The result is the
+/// lexicographic minimum over the six permutations which fix `pinned`.
+pub fn canonicalize_zones_with_pinned_suit(
+    cards: &mut [Card],
+    zones: &[ZoneSpec],
+    pinned: Suit,
+) -> SuitPermutation {
+    canonicalize_zones_with_assigned_suits(cards, zones, &[(pinned, pinned)])
+        .expect("a single suit can always be pinned")
+}
+
+/// Canonicalize a hand made of consecutive zones, with some suits already relabelled
+///
+/// Each `(original, canonical)` pair in `assigned` requires `original` to be
+/// relabelled as `canonical`, and the result is the lexicographic minimum over
+/// the remaining freedom. This keeps a hand consistent with state which was
+/// already relabelled elsewhere. Returns None, leaving `cards` untouched, if
+/// no permutation satisfies every pair.
+pub fn canonicalize_zones_with_assigned_suits(
+    cards: &mut [Card],
+    zones: &[ZoneSpec],
+    assigned: &[(Suit, Suit)],
+) -> Option<SuitPermutation> {
+    let mut candidates: Vec<_> = SuitPermutation::all()
+        .filter(|permutation| {
+            assigned
+                .iter()
+                .all(|(original, canonical)| permutation.get(*original) == *canonical)
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+    canonicalize_zones_in_place(cards, zones, &mut candidates);
+    Some(candidates[0])
+}
+
+/// Get the lexicographic minimum of a hand over relabellings consistent with `assigned`
+///
+/// As `canonicalize_zones_with_assigned_suits` for a hand whose first two
+/// cards are the hole cards, returning the canonical hand and the permutation
+/// applied. Returns None if the assignments contradict each other. Panics if
+/// there are fewer than two cards.
+pub fn canonicalize_hand_with_assigned_suits(
+    mut cards: Vec<Card>,
+    assigned: &[(Suit, Suit)],
+) -> Option<(Vec<Card>, SuitPermutation)> {
+    assert!(cards.len() >= 2, "hand has fewer than two cards");
+    let zones = [ZoneSpec::unordered(2), ZoneSpec::unordered(cards.len() - 2)];
+    let permutation = canonicalize_zones_with_assigned_suits(&mut cards, &zones, assigned)?;
+    Some((cards, permutation))
+}
+
+/// Get the lexicographic minimum of a hand over relabellings which leave `pinned` alone
+///
+/// The first two cards are the hole cards and the remainder is the board, as
+/// for `canonicalize_hand`. Panics if there are fewer than two cards.
+pub fn canonicalize_hand_with_pinned_suit(mut cards: Vec<Card>, pinned: Suit) -> Vec<Card> {
+    assert!(cards.len() >= 2, "hand has fewer than two cards");
+    let zones = [ZoneSpec::unordered(2), ZoneSpec::unordered(cards.len() - 2)];
+    canonicalize_zones_with_pinned_suit(&mut cards, &zones, pinned);
+    cards
+}
+
+/// Get the lexicographic minimum of a set of cards under suit relabelling
+///
+/// All the cards form one unordered group, with no hole cards, as for a
+/// rummy hand or a set of dead cards. Any number of cards is accepted, and
+/// the result is sorted.
+pub fn canonicalize_cards(cards: &[Card]) -> Vec<Card> {
+    let mut cards = cards.to_vec();
+    let zones = [ZoneSpec::unordered(cards.len())];
+    canonicalize_zones(&mut cards, &zones);
+    cards
+}
+
+/// Get strategically equivalent hand with lexicographic minimum, for any number of hole cards
+///
+/// The first `hole_count` cards are the hole cards and the remainder is the
+/// board; each part is sorted independently. Use 2 for Hold'em, 3 for
+/// Pineapple, 4 for Omaha and 5 for Big O. Panics if there are fewer than
+/// `hole_count` cards.
+pub fn canonicalize_hand_with_hole_count(mut cards: Vec<Card>, hole_count: usize) -> Vec<Card> {
+    assert!(
+        cards.len() >= hole_count,
+        "hand has fewer cards than the hole"
+    );
+    if hole_count == 2 {
+        return canonicalize_hand(cards);
+    }
+
+    let zones = [
+        ZoneSpec::unordered(hole_count),
+        ZoneSpec::unordered(cards.len() - hole_count),
+    ];
+    canonicalize_zones(&mut cards, &zones);
+    cards
+}
+
+/// Get strategically equivalent Omaha hand with lexicographic minimum
+///
+/// The first four cards are the hole cards and the remainder is the board;
+/// each part is sorted independently. Panics if there are fewer than four
+/// cards.
+pub fn canonicalize_omaha_hand(cards: Vec<Card>) -> Vec<Card> {
+    canonicalize_hand_with_hole_count(cards, 4)
+}
+
+/// Hold'em cards split by street.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HoldemStreets {
+    pub hole: [Card; 2],
+    pub flop: [Card; 3],
+    pub turn: Option<Card>,
+    pub river: Option<Card>,
+}
+
+/// Canonicalize a Hold'em hand keeping its street structure
+///
+/// The hole cards and the flop are each unordered, but the turn and river
+/// keep their identity: hands which differ only in which board card arrived
+/// on which street are distinct states in a game tree, so they canonicalize
+/// differently here even though `canonicalize_hand` merges them. The result is
+/// the lexicographic minimum comparing hole, flop, turn and river in order.
+///
+/// Panics if there is a river card but no turn card.
+pub fn canonicalize_holdem(
+    hole: [Card; 2],
+    flop: [Card; 3],
+    turn: Option<Card>,
+    river: Option<Card>,
+) -> HoldemStreets {
+    assert!(
+        turn.is_some() || river.is_none(),
+        "river dealt without a turn"
+    );
+
+    let mut cards = [
+        hole[0], hole[1], flop[0], flop[1], flop[2], hole[0], hole[0],
+    ];
+    let mut len = 5;
+    for card in turn.iter().chain(river.iter()) {
+        cards[len] = *card;
+        len += 1;
+    }
+
+    let zones = [
+        ZoneSpec::unordered(2),
+        ZoneSpec::unordered(3),
+        ZoneSpec::unordered(len.min(6) - 5),
+        ZoneSpec::unordered(len - len.min(6)),
+    ];
+    canonicalize_zones(&mut cards[..len], &zones);
+
+    HoldemStreets {
+        hole: [cards[0], cards[1]],
+        flop: [cards[2], cards[3], cards[4]],
+        turn: turn.map(|_| cards[5]),
+        river: river.map(|_| cards[6]),
+    }
+}
+
+/// Canonicalize a stud hand split into face-down and face-up cards
+///
+/// Up-cards and down-cards are never interchangeable, since opponents see one
+/// but not the other. The down-cards are unordered, while the up-cards keep
+/// the street order they were dealt in, as that order is part of the public
+/// action history. The result is the lexicographic minimum comparing the
+/// down-cards, then the up-cards.
+pub fn canonicalize_stud(down: &[Card], up: &[Card]) -> (Vec<Card>, Vec<Card>) {
+    let mut cards: Vec<Card> = down.iter().chain(up).copied().collect();
+    let zones = [ZoneSpec::unordered(down.len()), ZoneSpec::ordered(up.len())];
+    canonicalize_zones(&mut cards, &zones);
+
+    let up = cards.split_off(down.len());
+    (cards, up)
+}
+
+/// Canonicalize a draw-game hand together with the cards it has discarded
+///
+/// Discards can't come back but still block opponents' draws, so they are
+/// relabelled with the kept hand rather than dropped. Both parts are
+/// unordered, with discards from every draw pooled together; the result is the
+/// lexicographic minimum comparing the kept cards, then the discards.
+pub fn canonicalize_draw(kept: &[Card], discards: &[Card]) -> (Vec<Card>, Vec<Card>) {
+    let mut cards: Vec<Card> = kept.iter().chain(discards).copied().collect();
+    let zones = [
+        ZoneSpec::unordered(kept.len()),
+        ZoneSpec::unordered(discards.len()),
+    ];
+    canonicalize_zones(&mut cards, &zones);
+
+    let discards = cards.split_off(kept.len());
+    (cards, discards)
+}
+
+/// Canonicalize several players' hole cards and a shared board under one suit relabelling
+///
+/// Each player's hole cards are unordered, as is the board, but players keep
+/// their seats: the result is the lexicographic minimum comparing each
+/// player's hole cards in seat order, then the board. Canonicalizing players
+/// one at a time would relabel each of them differently.
+pub fn canonicalize_multiway(holes: &[[Card; 2]], board: &[Card]) -> (Vec<[Card; 2]>, Vec<Card>) {
+    let mut cards: Vec<Card> = holes.iter().flatten().chain(board).copied().collect();
+    let mut zones = vec![ZoneSpec::unordered(2); holes.len()];
+    zones.push(ZoneSpec::unordered(board.len()));
+    canonicalize_zones(&mut cards, &zones);
+
+    let board = cards.split_off(2 * holes.len());
+    let holes = cards
+        .chunks_exact(2)
+        .map(|hole| [hole[0], hole[1]])
+        .collect();
+    (holes, board)
+}
+
+/// Canonicalize a multiway deal where the opponents' seats are interchangeable
+///
+/// Like `canonicalize_multiway`, but only the first player (the hero) keeps
+/// their seat: the other players are treated as an unordered group, as in
+/// all-in equity enumeration where it doesn't matter which opponent holds
+/// which hand. The canonical form is the lexicographic minimum of the hero's
+/// hole cards, then the sorted list of opponents' hole cards, then the board.
+///
+/// Panics if `holes` is empty.
+pub fn canonicalize_multiway_with_seat_symmetry(
+    holes: &[[Card; 2]],
+    board: &[Card],
+) -> (Vec<[Card; 2]>, Vec<Card>) {
+    let (hero, opponents) = holes.split_first().expect("multiway deal needs a hero");
+    let mut candidates: Vec<_> = SuitPermutation::all().collect();
+
+    let mut hero = *hero;
+    canonicalize_zone_in_place(&mut hero, false, &mut candidates);
+
+    let mut best: Vec<[Card; 2]> = Vec::with_capacity(opponents.len());
+    let mut image = Vec::with_capacity(opponents.len());
+    let mut kept = 0;
+    for index in 0..candidates.len() {
+        let permutation = candidates[index];
+        image.clear();
+        image.extend(opponents.iter().map(|hole| {
+            let mut hole = [permutation.apply(hole[0]), permutation.apply(hole[1])];
+            hole.sort_unstable();
+            hole
+        }));
+        image.sort_unstable();
+
+        if kept == 0 || image < best {
+            std::mem::swap(&mut best, &mut image);
+            candidates[0] = permutation;
+            kept = 1;
+        } else if image == best {
+            candidates[kept] = permutation;
+            kept += 1;
+        }
+    }
+    candidates.truncate(kept);
+
+    let mut board = board.to_vec();
+    canonicalize_zone_in_place(&mut board, false, &mut candidates);
+
+    let mut holes = vec![hero];
+    holes.extend(best);
+    (holes, board)
+}
+
+/// Canonicalize hole cards dealt against two boards, as in a double-board bomb pot
+///
+/// Both boards share one suit relabelling with the hole cards. Each board is
+/// unordered, but the boards keep their identity: the result is the
+/// lexicographic minimum comparing the hole cards, then `board_a`, then
+/// `board_b`, so ties left after the first board are broken by the second.
+/// Swapping the boards can therefore give a different, but equally valid,
+/// canonical form.
+pub fn canonicalize_double_board(
+    hole: [Card; 2],
+    board_a: &[Card],
+    board_b: &[Card],
+) -> ([Card; 2], Vec<Card>, Vec<Card>) {
+    let mut cards: Vec<Card> = hole.iter().chain(board_a).chain(board_b).copied().collect();
+    let zones = [
+        ZoneSpec::unordered(2),
+        ZoneSpec::unordered(board_a.len()),
+        ZoneSpec::unordered(board_b.len()),
+    ];
+    canonicalize_zones(&mut cards, &zones);
+
+    let board_b = cards.split_off(2 + board_a.len());
+    let board_a = cards.split_off(2);
+    ([cards[0], cards[1]], board_a, board_b)
+}
+
+/// Canonicalize a board which was run twice from a shared prefix
+///
+/// `prefix` is the board dealt before the players agreed to run it twice, and
+/// each runout is the cards dealt after it, street by street. One suit
+/// relabelling covers everything. The hole cards and prefix are unordered.
+/// Within each runout any flop cards are unordered, while the turn and river
+/// keep their street order, and the runouts keep their identity: the result
+/// is the lexicographic minimum comparing the hole cards, the prefix, the
+/// first runout, then the second.
+///
+/// Panics unless the prefix and runouts each end on a street, i.e. the prefix
+/// has 0, 3, 4 or 5 cards and the full board 3, 4 or 5, or if the runouts have
+/// different lengths.
+pub fn canonicalize_run_it_twice(
+    hole: [Card; 2],
+    prefix: &[Card],
+    runouts: [&[Card]; 2],
+) -> ([Card; 2], Vec<Card>, [Vec<Card>; 2]) {
+    assert_eq!(
+        runouts[0].len(),
+        runouts[1].len(),
+        "runouts must deal the same streets"
+    );
+    let runout_len = runouts[0].len();
+    assert!(
+        matches!(prefix.len(), 0 | 3..=5) && matches!(prefix.len() + runout_len, 3..=5),
+        "prefix and runouts must end on a street"
+    );
+    // a runout from an empty prefix deals the whole flop
+    let flop_len = if prefix.is_empty() {
+        runout_len.min(3)
+    } else {
+        0
+    };
+
+    let mut cards: Vec<Card> = hole
+        .iter()
+        .chain(prefix)
+        .chain(runouts[0])
+        .chain(runouts[1])
+        .copied()
+        .collect();
+    let zones = [
+        ZoneSpec::unordered(2),
+        ZoneSpec::unordered(prefix.len()),
+        ZoneSpec::unordered(flop_len),
+        ZoneSpec::ordered(runout_len - flop_len),
+        ZoneSpec::unordered(flop_len),
+        ZoneSpec::ordered(runout_len - flop_len),
+    ];
+    canonicalize_zones(&mut cards, &zones);
+
+    let second = cards.split_off(2 + prefix.len() + runout_len);
+    let first = cards.split_off(2 + prefix.len());
+    let prefix = cards.split_off(2);
+    ([cards[0], cards[1]], prefix, [first, second])
+}